@@ -80,20 +80,18 @@ pub fn remove_comments(file_content: &str) -> String {
                 }
             },
             ParserState::MultiLineComment => {
-                match ch {
-                    b'*' => state = ParserState::MultiLineCommentAsterisk,
-                    _ => (),
+                if ch == b'*' {
+                    state = ParserState::MultiLineCommentAsterisk;
                 }
             },
             ParserState::MultiLineCommentAsterisk => {
-                match ch {
-                    b'/' => state = ParserState::Normal,
-                    _ => (),
+                if ch == b'/' {
+                    state = ParserState::Normal;
                 }
             }
         }
     }
-    return result;
+    result
 }
 
 #[cfg(test)]
@@ -0,0 +1,291 @@
+use crate::declaration_finder::Dialect;
+use crate::header_db::{Standard, BUILTIN_DBS};
+use crate::toml::{self, Table, Value};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the project configuration file
+pub const CONFIG_FILE_NAME: &str = "code_analyser.toml";
+
+/// Project configuration, read from `code_analyser.toml`
+///
+/// Example:
+///
+/// ```toml
+/// dialect = "c++"
+/// std = "c++17"
+/// skip_identifiers = ["MYLIB_INLINE"]
+/// include_paths = ["include"]
+/// defines = ["NDEBUG", "MYLIB_VERSION=3"]
+/// standard_headers_db = "tools/standard_headers.toml"
+/// header_dbs = ["posix", "linux", "tools/mylib_headers.toml"]
+/// mapping_files = ["tools/mylib.imp"]
+///
+/// [replace]
+/// FINAL = "final"
+/// MYLIB_API = ""
+///
+/// [checks.unused-standard-headers]
+/// ignore = ["cassert"]
+///
+/// [checks.missing-standard-headers]
+/// ignore = ["cstddef"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config
+{
+    // Language of the sources, detected from file names if not set
+    pub dialect: Option<Dialect>,
+    // C++ standard the sources are written in, facilities of the standard library are checked against it
+    pub standard: Option<Standard>,
+    // Identifiers removed before parsing, e.g. project macros expanding to nothing
+    pub skip_identifiers: Vec<String>,
+    // Identifiers replaced before parsing, e.g. `FINAL` with `final`; an empty replacement removes the identifier
+    pub replacements: BTreeMap<String, String>,
+    // Directories to look for headers in, relative paths are resolved against the configuration file directory
+    pub include_paths: Vec<PathBuf>,
+    // Object-like macros, `NAME` defines `NAME` as `1`, `NAME=VALUE` defines it as `VALUE`
+    pub defines: BTreeMap<String, String>,
+    // Database of symbols of the standard headers, overriding the headers of the built-in one
+    pub standard_headers_db: Option<PathBuf>,
+    // Additional databases of symbols of headers, names of built-in ones like `posix` or file paths
+    pub header_dbs: Vec<String>,
+    // include-what-you-use mapping files (`.imp`) applied to the databases of headers
+    pub mapping_files: Vec<PathBuf>,
+    // Options of the individual checks, by the check name
+    pub checks: BTreeMap<String, Table>,
+}
+
+impl Config
+{
+    /// Parse configuration file content, resolving relative paths against `base_dir`
+    pub fn parse(content: &str, base_dir: &Path) -> Result<Config, String>
+    {
+        let mut config = Config::default();
+        for (key, value) in toml::parse(content)?
+        {
+            match key.as_str()
+            {
+                "dialect" => {
+                    let name = value.as_str().ok_or("`dialect` must be a string")?;
+                    config.dialect = Some(Dialect::from_name(name).ok_or(format!("unknown dialect `{}`", name))?);
+                },
+                "std" => {
+                    let name = value.as_str().ok_or("`std` must be a string")?;
+                    config.standard = Some(Standard::from_name(name).ok_or(format!("unknown standard `{}`", name))?);
+                },
+                "skip_identifiers" => config.skip_identifiers = string_array(&key, &value)?,
+                "include_paths" => {
+                    config.include_paths = string_array(&key, &value)?.iter().map(|path| base_dir.join(path)).collect();
+                },
+                "standard_headers_db" => {
+                    let path = value.as_str().ok_or("`standard_headers_db` must be a string")?;
+                    config.standard_headers_db = Some(base_dir.join(path));
+                },
+                "header_dbs" => {
+                    for db in string_array(&key, &value)?
+                    {
+                        let is_builtin = BUILTIN_DBS.contains(&db.as_str());
+                        config.header_dbs.push(if is_builtin { db } else { base_dir.join(db).to_string_lossy().into() });
+                    }
+                },
+                "mapping_files" => {
+                    config.mapping_files = string_array(&key, &value)?.iter().map(|path| base_dir.join(path)).collect();
+                },
+                "defines" => {
+                    for define in string_array(&key, &value)?
+                    {
+                        config.add_define(&define);
+                    }
+                },
+                "replace" => {
+                    for (identifier, replacement) in table(&key, &value)?
+                    {
+                        let replacement = replacement.as_str().ok_or(format!("replacement of `{}` must be a string", identifier))?;
+                        config.replacements.insert(identifier.clone(), replacement.into());
+                    }
+                },
+                "checks" => {
+                    for (check, options) in table(&key, &value)?
+                    {
+                        config.checks.insert(check.clone(), table(check, options)?.clone());
+                    }
+                },
+                _ => return Err(format!("unknown key `{}`", key)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Read a configuration file
+    pub fn load(path: &Path) -> Result<Config, String>
+    {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Config::parse(&content, base_dir)
+    }
+
+    /// Read the configuration file of a source file, or get the default configuration if there is none
+    ///
+    /// Errors are prefixed with the configuration file path.
+    pub fn for_file(file_name: &str) -> Result<Config, String>
+    {
+        match find_config_file(file_name)
+        {
+            Some(path) => Config::load(&path).map_err(|e| format!("{}: {}", path.display(), e)),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Define an object-like macro from `NAME` or `NAME=VALUE`
+    pub fn add_define(&mut self, define: &str)
+    {
+        match define.split_once('=')
+        {
+            Some((name, value)) => self.defines.insert(name.into(), value.into()),
+            None => self.defines.insert(define.into(), "1".into()),
+        };
+    }
+
+    /// Apply `overrides` on top of this configuration, e.g. options given on the command line
+    ///
+    /// Lists are extended, and values of the same key are replaced.
+    pub fn merge(&mut self, overrides: Config)
+    {
+        if overrides.dialect.is_some()
+        {
+            self.dialect = overrides.dialect;
+        }
+        if overrides.standard.is_some()
+        {
+            self.standard = overrides.standard;
+        }
+        self.skip_identifiers.extend(overrides.skip_identifiers);
+        self.replacements.extend(overrides.replacements);
+        self.include_paths.extend(overrides.include_paths);
+        self.header_dbs.extend(overrides.header_dbs);
+        self.mapping_files.extend(overrides.mapping_files);
+        self.defines.extend(overrides.defines);
+        if overrides.standard_headers_db.is_some()
+        {
+            self.standard_headers_db = overrides.standard_headers_db;
+        }
+        for (check, options) in overrides.checks
+        {
+            self.checks.entry(check).or_default().extend(options);
+        }
+    }
+
+    /// Replacements applied to identifiers before parsing, including defines
+    pub fn identifier_replacements(&self) -> BTreeMap<String, String>
+    {
+        let mut res = self.defines.clone();
+        res.extend(self.replacements.clone());
+        for identifier in &self.skip_identifiers
+        {
+            res.insert(identifier.clone(), String::new());
+        }
+        res
+    }
+
+    /// Get an option of a check, e.g. `ignore` of `unused-standard-headers`
+    pub fn check_option(&self, check: &str, option: &str) -> Option<&Value>
+    {
+        self.checks.get(check)?.get(option)
+    }
+}
+
+fn string_array(key: &str, value: &Value) -> Result<Vec<String>, String>
+{
+    value.as_string_array().ok_or(format!("`{}` must be an array of strings", key))
+}
+
+fn table<'a>(key: &str, value: &'a Value) -> Result<&'a Table, String>
+{
+    value.as_table().ok_or(format!("`{}` must be a table", key))
+}
+
+/// Find the configuration file of a source file, looking in its directory and all the parent directories
+///
+/// The search starts in the current directory for the standard input (`-`).
+pub fn find_config_file(file_name: &str) -> Option<PathBuf>
+{
+    let path = if file_name == "-" { PathBuf::from(".") } else { PathBuf::from(file_name) };
+    let path = fs::canonicalize(path).ok()?;
+    let mut dir = if path.is_dir() { Some(path.as_path()) } else { path.parent() };
+    while let Some(d) = dir
+    {
+        let candidate = d.join(CONFIG_FILE_NAME);
+        if candidate.is_file()
+        {
+            return Some(candidate);
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"
+            dialect = "c"
+            std = "c++17"
+            skip_identifiers = ["MYLIB_INLINE"]
+            include_paths = ["include", "/usr/include/mylib"]
+            defines = ["NDEBUG", "VERSION=3"]
+            standard_headers_db = "db.toml"
+            header_dbs = ["posix", "mylib.toml"]
+            mapping_files = ["mylib.imp"]
+
+            [replace]
+            FINAL = "final"
+            MYLIB_API = ""
+
+            [checks.unused-standard-headers]
+            ignore = ["cassert"]
+        "#;
+        let config = Config::parse(input, Path::new("/project")).unwrap();
+        assert_eq!(config.dialect, Some(Dialect::C));
+        assert_eq!(config.standard, Some(Standard::Cpp17));
+        assert_eq!(config.include_paths, vec![PathBuf::from("/project/include"), PathBuf::from("/usr/include/mylib")]);
+        assert_eq!(config.standard_headers_db, Some(PathBuf::from("/project/db.toml")));
+        assert_eq!(config.header_dbs, vec!["posix".to_string(), "/project/mylib.toml".into()]);
+        assert_eq!(config.mapping_files, vec![PathBuf::from("/project/mylib.imp")]);
+        assert_eq!(config.check_option("unused-standard-headers", "ignore").and_then(Value::as_string_array), Some(vec!["cassert".into()]));
+
+        let replacements: Vec<(String, String)> = config.identifier_replacements().into_iter().collect();
+        assert_eq!(replacements, vec![
+            ("FINAL".into(), "final".into()),
+            ("MYLIB_API".into(), "".into()),
+            ("MYLIB_INLINE".into(), "".into()),
+            ("NDEBUG".into(), "1".into()),
+            ("VERSION".into(), "3".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Config::parse("dialect = \"pascal\"", Path::new(".")), Err("unknown dialect `pascal`".into()));
+        assert_eq!(Config::parse("skip_identifiers = \"A\"", Path::new(".")), Err("`skip_identifiers` must be an array of strings".into()));
+        assert_eq!(Config::parse("std = \"c++21\"", Path::new(".")), Err("unknown standard `c++21`".into()));
+        assert_eq!(Config::parse("skip = []", Path::new(".")), Err("unknown key `skip`".into()));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut config = Config::parse("dialect = \"c\"\n[replace]\nFINAL = \"final\"", Path::new(".")).unwrap();
+        let mut overrides = Config { dialect: Some(Dialect::Cpp), ..Config::default() };
+        overrides.replacements.insert("FINAL".into(), "".into());
+        overrides.add_define("DEBUG");
+        config.merge(overrides);
+        assert_eq!(config.dialect, Some(Dialect::Cpp));
+        assert_eq!(config.replacements["FINAL"], "");
+        assert_eq!(config.defines["DEBUG"], "1");
+    }
+}
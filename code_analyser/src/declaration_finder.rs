@@ -1,773 +1,3584 @@
 use crate::tokenizer::Token;
 use crate::tokenizer::tokenize;
 use crate::preprocessor::get_preprocessor_definitions;
+use crate::config::Config;
 
-use std::env;
+use std::collections::BTreeMap;
+use std::fmt;
 
-struct DeclarationFinder
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeclarationKind
 {
-    tokens: Vec<Token>,
-    pos: usize,
-    declarations: Vec<String>,
-    closing_curly_brackets_expected: usize,
+    Macro,
+    Variable,
+    Function,
+    Class,
+    Struct,
+    Union,
+    Enum,
+    Enumerator,
+    Typedef,
+    // using A = B;
+    // template <typename T> using A = B<T>;
+    TypeAlias,
+    // namespace A = B::C;
+    NamespaceAlias,
+    // using A::b;
+    UsingDeclaration,
+    // using enum A;
+    UsingEnum,
+    // using namespace A;
+    UsingDirective,
+    // template <typename T> concept A = ...;
+    Concept,
+    // export module a.b:c;
+    Module,
+    // import a.b;
+    // import <vector>;
+    Import,
 }
 
-impl DeclarationFinder
+/// Language of the parsed file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect
 {
-    fn eof(&self) -> bool
-    {
-        self.pos >= self.tokens.len()
-    }
+    C,
+    Cpp,
+}
 
-    fn token(&self) -> &Token
+impl Dialect
+{
+    /// Dialect of a source file by its extension, `.c` files are C, everything else is C++
+    pub fn from_file_name(file_name: &str) -> Dialect
     {
-        &self.tokens[self.pos]
+        if file_name.ends_with(".c") { Dialect::C } else { Dialect::Cpp }
     }
 
-    fn skip_token(&mut self)
+    /// Dialect by its name, e.g. `c` or `c++`
+    pub fn from_name(name: &str) -> Option<Dialect>
     {
-        self.pos += 1;
+        match name.to_lowercase().as_str()
+        {
+            "c" => Some(Dialect::C),
+            "c++" | "cpp" | "cxx" => Some(Dialect::Cpp),
+            _ => None,
+        }
     }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access
+{
+    Public,
+    Protected,
+    Private,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Specifier
+{
+    Virtual,
+    Override,
+    Static,
+    Extern,
+    Friend,
+    // = default
+    Default,
+    // = delete
+    Delete,
+    // = 0
+    PureVirtual,
+    Inline,
+    Constexpr,
+    Consteval,
+    // thread_local, _Thread_local or __thread
+    ThreadLocal,
+}
+
+/// Whether a name can be referred to from other scopes or translation units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Linkage
+{
+    // Functions, variables and types visible from other translation units
+    External,
+    // `static` functions and variables, `const` variables in C++ and names in anonymous namespaces
+    Internal,
+    // Typedefs, aliases, macros, non-static data members and unnamed types
+    None,
+}
+
+/// Language linkage of a function or a variable with external linkage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LanguageLinkage
+{
+    // Declared in a C file, or in C++ inside `extern "C" { ... }` or after `extern "C"`
+    C,
+    Cpp,
+}
+
+/// Storage duration of a variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageDuration
+{
+    // Variables at namespace scope, `static` and `extern` variables, and static data members
+    Static,
+    // `thread_local` variables
+    Thread,
+    // Variables at block scope. Function bodies are skipped, so they are not reported.
+    Automatic,
+}
+
+/// How a declaration relates to its primary template
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Specialization
+{
+    // template <> struct A<int> { ... };
+    Explicit,
+    // template <typename T> struct A<T*> { ... };
+    Partial,
+    // template struct A<int>;
+    Instantiation,
+    // extern template struct A<int>;
+    ExternInstantiation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeSyntax
+{
+    // [[nodiscard]]
+    Standard,
+    // __attribute__((packed))
+    Gnu,
+    // __declspec(dllexport)
+    Declspec,
+    // alignas(16)
+    Alignas,
+}
+
+/// An attribute attached to a declaration, e.g. `[[deprecated("use g")]]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute
+{
+    pub syntax: AttributeSyntax,
+    // Name, possibly with a namespace, e.g. `nodiscard`, `gnu::packed` or `dllexport`
+    pub name: String,
+    // Arguments as written, without the parentheses, e.g. `"use g"` or `16`
+    pub arguments: Option<String>,
+}
 
-    fn add_declaration(&mut self, declaration: String)
+impl Attribute
+{
+    /// Name without a namespace and underscores, e.g. `deprecated` for `gnu::__deprecated__`
+    pub fn base_name(&self) -> &str
     {
-        if !self.declarations.contains(&declaration)
+        let (_, name) = split_qualified_name(&self.name);
+        match name.strip_prefix("__").and_then(|name| name.strip_suffix("__"))
         {
-            self.declarations.push(declaration.into());
+            Some(name) => name,
+            None => name,
         }
     }
 }
 
-/// Filter out unneeded tokens to simplify processing
-///
-/// 1. Remove preprocessor directives
-/// 2. Remove whitespace (Token::WhiteSpace and Token::NewLine)
-/// 3. Remove comments
-/// 4. Remove unnecessary keywords
-fn filter_tokens(input_tokens: Vec::<Token>) -> Vec::<Token>
+/// A function parameter or a template parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter
 {
-    let mut res = Vec::<Token>::new();
-    let mut is_in_preprocessor = false;
+    // Type without the name, e.g. `const std::string&`, `int[10]` or `typename`
+    pub type_name: String,
+    // `None` for unnamed parameters, e.g. `int` in `void f(int);`
+    pub name: Option<String>,
+    // Default argument as written, e.g. `nullptr` or `std::vector<int>`
+    pub default_value: Option<String>,
+}
 
-    // Projects may define its macro that interfere with parsing
-    // One of examples would be codintionally use `final` keyword, e.g.:
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FunctionSignature
+{
+    // `template <...>` parameters, empty for explicit specialisations `template <>`
+    pub template_parameters: Option<Vec<Parameter>>,
+    // Constraints as written, e.g. `std::integral<T>`
     //
-    // #ifdef BUILD_ON_TARGET
-    //   #define FINAL final
-    // #else // test build
-    //   #define FINAL
-    // #endif
+    // Clauses after the template parameters and after the function parameters are combined with `&&`.
+    pub requires_clause: Option<String>,
+    // Return type, e.g. `const char*`
     //
-    // class A FINAL: class B {}
-    let binding = env::var("SKIP_IDENTIFIERS").unwrap_or("".to_string());
-    let identifiers_to_skip: Vec<&str> = binding.split(",").collect();
+    // A trailing return type replaces `auto`, e.g. `int` for `auto f() -> int`.
+    // It is empty for constructors, destructors and conversion functions.
+    pub return_type: String,
+    pub parameters: Vec<Parameter>,
+    // C-style variadic function, e.g. `int printf(const char* format, ...);`
+    pub is_variadic: bool,
+    pub is_const: bool,
+    pub is_volatile: bool,
+    // `&` or `&&`
+    pub ref_qualifier: Option<String>,
+    // `noexcept` or `noexcept(...)` as written
+    pub noexcept: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration
+{
+    // Name qualified with the enclosing namespaces and classes, e.g. `a::b::x`
+    //
+    // Anonymous and inline namespaces are not part of the name.
+    pub name: String,
+    pub kind: DeclarationKind,
+    pub linkage: Linkage,
+    // `None` for declarations without external linkage and for class members
+    pub language_linkage: Option<LanguageLinkage>,
+    // `None` for declarations other than variables, and for non-static data members
+    pub storage_duration: Option<StorageDuration>,
+    // Exported from a module, e.g. `export int f();` or a declaration inside `export { ... }`
+    //
+    // Class members are not marked, even if the class is exported.
+    pub is_exported: bool,
+    // Access of a class member, `None` outside classes
+    pub access: Option<Access>,
+    pub specifiers: Vec<Specifier>,
+    // Members of a class or struct
+    pub members: Vec<Declaration>,
+    // Function with a body, variable that is not `extern` or a static data member declaration,
+    // class or enum with a body
+    pub is_definition: bool,
+    // Qualifier of the declared name, e.g. `a::Foo` for `void Foo::bar() {}` inside `namespace a`
+    //
+    // It is normally a class, but it can't be distinguished from a namespace
+    // without seeing its declaration.
+    pub class_name: Option<String>,
+    // Parameters, return type and qualifiers of a function
+    pub signature: Option<FunctionSignature>,
+    pub attributes: Vec<Attribute>,
+    // Type of a variable or a typedef, e.g. `const char*` or `S` for `struct S { ... } s;`
+    pub type_name: Option<String>,
+    // Variable initialised with a lambda expression, e.g. `auto f = [](int x) { return x; };`
+    pub is_lambda: bool,
+    // Underlying type of an enum, e.g. `std::uint8_t` for `enum class E : std::uint8_t`
+    pub underlying_type: Option<String>,
+    // Initialiser of an enumerator or the constraint of a concept as written, e.g. `1 << 3`
+    pub initializer: Option<String>,
+    // What a `using` refers to, e.g. `std::vector<T>` for `using Vec = std::vector<T>;`
+    // or `std::swap` for `using std::swap;`
+    //
+    // The name of a using-declaration, using-enum or using-directive is the last component of the target.
+    pub target: Option<String>,
+    // `template <...>` parameters of a class, alias template or concept
+    pub template_parameters: Option<Vec<Parameter>>,
+    // Specialisation or explicit instantiation of the template named by `name`
+    pub specialization: Option<Specialization>,
+    // Arguments of a specialisation or an explicit instantiation as written, e.g. `int` for `template <> struct hash<int>`
+    pub template_arguments: Option<String>,
+    // Constraints of a class template as written, e.g. `std::copyable<T>`
+    pub requires_clause: Option<String>,
+}
 
-    'outer: for token in input_tokens.into_iter()
+impl Declaration
+{
+    pub fn new(name: &str, kind: DeclarationKind) -> Declaration
     {
-        if is_in_preprocessor
+        Declaration {
+            name: name.into(),
+            kind,
+            linkage: Linkage::External,
+            language_linkage: None,
+            storage_duration: None,
+            is_exported: false,
+            access: None,
+            specifiers: Vec::new(),
+            members: Vec::new(),
+            is_definition: false,
+            class_name: None,
+            signature: None,
+            attributes: Vec::new(),
+            type_name: None,
+            is_lambda: false,
+            underlying_type: None,
+            initializer: None,
+            target: None,
+            template_parameters: None,
+            specialization: None,
+            template_arguments: None,
+            requires_clause: None,
+        }
+    }
+
+    /// Check if the declaration has an attribute in any syntax,
+    /// e.g. `deprecated` matches `[[deprecated]]`, `__attribute__((__deprecated__))` and `__declspec(deprecated)`
+    pub fn has_attribute(&self, name: &str) -> bool
+    {
+        self.attributes.iter().any(|attribute| attribute.base_name() == name)
+    }
+
+    /// Check if it is an operator overload or a conversion function, e.g. `operator==` or `operator bool`
+    pub fn is_operator(&self) -> bool
+    {
+        is_operator_name(split_qualified_name(&self.name).1)
+    }
+
+    /// Normalised prototype of a function, e.g. `static int f(const char* format, ...) noexcept`
+    ///
+    /// `None` for declarations other than functions
+    pub fn prototype(&self) -> Option<String>
+    {
+        let signature = self.signature.as_ref()?;
+        let mut res = String::new();
+
+        match self.specialization
         {
-            if let Token::NewLine(_) = token
+            Some(Specialization::Instantiation) => res += "template ",
+            Some(Specialization::ExternInstantiation) => res += "extern template ",
+            _ => {},
+        }
+        if let Some(template_parameters) = &signature.template_parameters
+        {
+            let template_parameters: Vec<String> = template_parameters.iter().map(|p| p.to_string()).collect();
+            res += &format!("template <{}> ", template_parameters.join(", "));
+        }
+        if let Some(requires_clause) = &signature.requires_clause
+        {
+            res += &format!("requires {} ", requires_clause);
+        }
+        for specifier in &self.specifiers
+        {
+            if matches!(specifier, Specifier::Virtual | Specifier::Static | Specifier::Extern | Specifier::Friend
+                | Specifier::Inline | Specifier::Constexpr | Specifier::Consteval)
             {
-                is_in_preprocessor = false;
+                res += &format!("{} ", specifier);
             }
-            continue;
         }
 
-        if matches!(token, Token::WhiteSpace(_) | Token::NewLine(_) | Token::Comment(_))
+        let mut parameters: Vec<String> = signature.parameters.iter().map(|p| p.to_string()).collect();
+        if signature.is_variadic
         {
-            // skip
+            parameters.push("...".into());
         }
-        else if let Token::Operator(s) = token
+        let mut declarator = self.name.clone();
+        if let Some(template_arguments) = &self.template_arguments
         {
-            if s == "#"
-            {
-                is_in_preprocessor = true;
-            }
-            else
-            {
-                res.push(Token::Operator(s));
-            }
+            declarator += &format!("<{}>", template_arguments);
         }
-        else if let Token::Identifier(ref s) = token
+        declarator += &format!("({})", parameters.join(", "));
+        if signature.is_const
         {
-            for keyword in vec!["const", "final"]
-            {
-                if s == keyword
-                {
-                    // skip
-                    continue 'outer;
-                }
-            }
+            declarator += " const";
+        }
+        if signature.is_volatile
+        {
+            declarator += " volatile";
+        }
+        if let Some(ref_qualifier) = &signature.ref_qualifier
+        {
+            declarator += &format!(" {}", ref_qualifier);
+        }
+        if let Some(noexcept) = &signature.noexcept
+        {
+            declarator += &format!(" {}", noexcept);
+        }
 
-            for keyword in &identifiers_to_skip
+        // A function returning a pointer to a function or an array goes inside the return type,
+        // e.g. `int (*make(int))(double)`
+        match abstract_declarator_end(&signature.return_type)
+        {
+            Some(pos) => res += &format!("{}{}{}", &signature.return_type[..pos], declarator, &signature.return_type[pos..]),
+            None if signature.return_type.is_empty() => res += &declarator,
+            None => res += &format!("{} {}", signature.return_type, declarator),
+        }
+        for specifier in &self.specifiers
+        {
+            if matches!(specifier, Specifier::Override | Specifier::Default | Specifier::Delete | Specifier::PureVirtual)
             {
-                if s == keyword
-                {
-                    // skip
-                    continue 'outer;
-                }
+                res += &format!(" {}", specifier);
             }
+        }
 
-            res.push(token);
+        Some(res)
+    }
+}
+
+/// Position of `)` closing the pointer or reference part of a type, where a declared name goes,
+/// e.g. the first `)` of `int (*)(double)` or `int (&)[10]`
+fn abstract_declarator_end(type_name: &str) -> Option<usize>
+{
+    let mut start = 0;
+    while let Some(open) = type_name[start..].find('(').map(|pos| start + pos)
+    {
+        let close = open + type_name[open..].find(')')?;
+        let content = type_name[open + 1..close].trim_end_matches(" const").trim_end_matches(" volatile");
+        if content.ends_with(['*', '&'])
+        {
+            return Some(close);
         }
-        else
+        start = open + 1;
+    }
+    None
+}
+
+impl fmt::Display for Access
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
         {
-            res.push(token);
+            Access::Public => write!(f, "public"),
+            Access::Protected => write!(f, "protected"),
+            Access::Private => write!(f, "private"),
         }
     }
+}
 
-    res
+impl fmt::Display for Specifier
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Specifier::Virtual => write!(f, "virtual"),
+            Specifier::Override => write!(f, "override"),
+            Specifier::Static => write!(f, "static"),
+            Specifier::Extern => write!(f, "extern"),
+            Specifier::Friend => write!(f, "friend"),
+            Specifier::Default => write!(f, "= default"),
+            Specifier::Delete => write!(f, "= delete"),
+            Specifier::PureVirtual => write!(f, "= 0"),
+            Specifier::Inline => write!(f, "inline"),
+            Specifier::Constexpr => write!(f, "constexpr"),
+            Specifier::Consteval => write!(f, "consteval"),
+            Specifier::ThreadLocal => write!(f, "thread_local"),
+        }
+    }
 }
 
-impl DeclarationFinder
+impl fmt::Display for Specialization
 {
-    fn dump(&self)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        for (i, token) in self.tokens.iter().enumerate()
+        match self
         {
-            let marker = if self.pos == i {" <--------------------------"} else {""};
-            println!("{} {:?} {}", i, token, marker);
+            Specialization::Explicit => write!(f, "explicit specialisation"),
+            Specialization::Partial => write!(f, "partial specialisation"),
+            Specialization::Instantiation => write!(f, "explicit instantiation"),
+            Specialization::ExternInstantiation => write!(f, "extern explicit instantiation"),
         }
     }
+}
 
-    fn is_identifier(&self, value: &str) -> bool
+impl fmt::Display for Attribute
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        *self.token() == Token::Identifier(value.into())
+        let attribute = match &self.arguments
+        {
+            Some(arguments) => format!("{}({})", self.name, arguments),
+            None => self.name.clone(),
+        };
+        match self.syntax
+        {
+            AttributeSyntax::Standard => write!(f, "[[{}]]", attribute),
+            AttributeSyntax::Gnu => write!(f, "__attribute__(({}))", attribute),
+            AttributeSyntax::Declspec => write!(f, "__declspec({})", attribute),
+            AttributeSyntax::Alignas => write!(f, "{}", attribute),
+        }
     }
+}
 
-    fn assert_not_eof(&self, error_msg: &str)
+impl fmt::Display for Parameter
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        if self.eof()
+        match (&self.name, self.type_name.find('['))
+        {
+            // The name of an array goes before brackets, e.g. `int a[10]`
+            (Some(name), Some(pos)) => write!(f, "{} {}{}", &self.type_name[..pos], name, &self.type_name[pos..])?,
+            (Some(name), None) => write!(f, "{} {}", self.type_name, name)?,
+            (None, _) => write!(f, "{}", self.type_name)?,
+        }
+        if let Some(default_value) = &self.default_value
         {
-            panic!("{}", error_msg);
+            write!(f, " = {}", default_value)?;
         }
+        Ok(())
     }
+}
 
-    fn skip_exact_token(&mut self, token_to_skip: &Token)
+impl fmt::Display for Declaration
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        if self.eof()
+        let mut markers = Vec::<String>::new();
+        if self.linkage == Linkage::Internal
         {
-            panic!("skip_exact_token: {:?} expected, EOF found", token_to_skip);
+            markers.push("internal linkage".into());
         }
-        if self.token() != token_to_skip
+        if self.language_linkage == Some(LanguageLinkage::C)
         {
-            panic!("skip_exact_token: {:?} expected, {:?} found", token_to_skip, self.token());
+            markers.push("C language linkage".into());
         }
-        self.skip_token();
+        if self.is_exported
+        {
+            markers.push("exported".into());
+        }
+        if let Some(specialization) = self.specialization
+        {
+            markers.push(specialization.to_string());
+        }
+        if let Some(access) = self.access
+        {
+            markers.push(access.to_string());
+        }
+        for specifier in &self.specifiers
+        {
+            markers.push(specifier.to_string());
+        }
+        if self.is_lambda
+        {
+            markers.push("lambda".into());
+        }
+
+        match (&self.kind, &self.target)
+        {
+            (DeclarationKind::UsingDeclaration, Some(target)) => write!(f, "using {}", target)?,
+            (DeclarationKind::UsingEnum, Some(target)) => write!(f, "using enum {}", target)?,
+            (DeclarationKind::UsingDirective, Some(target)) => write!(f, "using namespace {}", target)?,
+            (DeclarationKind::Module, _) => write!(f, "module {}", self.name)?,
+            (DeclarationKind::Import, _) => write!(f, "import {}", self.name)?,
+            _ => write!(f, "{}", self.name)?,
+        }
+        if let Some(template_arguments) = &self.template_arguments
+        {
+            write!(f, "<{}>", template_arguments)?;
+        }
+        if let Some(underlying_type) = &self.underlying_type
+        {
+            write!(f, " : {}", underlying_type)?;
+        }
+        if let Some(type_name) = &self.type_name
+        {
+            write!(f, " : {}", type_name)?;
+        }
+        if let Some(initializer) = &self.initializer
+        {
+            write!(f, " = {}", initializer)?;
+        }
+        for attribute in &self.attributes
+        {
+            write!(f, " {}", attribute)?;
+        }
+        if !markers.is_empty()
+        {
+            write!(f, " [{}]", markers.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem found while parsing a file, e.g. an unsupported construct
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic
+{
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Describe a token found where something else was expected, e.g. `` `;` `` or end of file
+fn describe_token(token: &Token) -> String
+{
+    match token
+    {
+        Token::Unknown(s) if s.is_empty() => "end of file".into(),
+        token => format!("`{}`", token),
     }
+}
+
+/// A block opened with `{` that is not skipped, but parsed declaration by declaration
+enum Scope
+{
+    // namespace a::b { ... }
+    //
+    // `names` is empty for anonymous namespaces and does not contain inline namespaces
+    Namespace { names: Vec<String>, anonymous: bool, exported: bool },
+    // extern "C" { ... }
+    LinkageSpecification { language: LanguageLinkage },
+    // export { ... }
+    Export,
+    // class A { ... }
+    Class { name: String },
+}
+
+/// Where a token is in the original file
+struct TokenPosition
+{
+    line: usize,
+    // Preceded by whitespace or a comment
+    after_space: bool,
+}
+
+struct DeclarationFinder
+{
+    dialect: Dialect,
+    tokens: Vec<Token>,
+    positions: Vec<TokenPosition>,
+    pos: usize,
+    // Declarations of the current scope: a file or a class body
+    declarations: Vec<Declaration>,
+    scopes: Vec<Scope>,
+    // Access of the members of the class being parsed
+    access: Option<Access>,
+    // Specifiers collected while parsing the current declaration
+    specifiers: Vec<Specifier>,
+    // Parameters of the `template <...>` preceding the current declaration
+    template_parameters: Option<Vec<Parameter>>,
+    // Requires-clause following `template <...>`
+    requires_clause: Option<String>,
+    // The current declaration is preceded by `export`
+    exported: bool,
+    // Language linkage given by `extern "C"` preceding the current declaration
+    language_linkage: Option<LanguageLinkage>,
+    // `template <>`, `template` or `extern template` preceding the current declaration
+    specialization: Option<Specialization>,
+    // Template arguments following the last name read, e.g. `int` for `hash<int>`
+    template_arguments: Option<String>,
+    // Attributes collected while parsing the current declaration
+    attributes: Vec<Attribute>,
+    // Position of the first token of the name read by get_declaration
+    name_start: usize,
+    // Position after the last token of the name read by get_declaration
+    name_end: usize,
+    // Position of `(` opening a nested declarator read by get_declaration, e.g. `(*f)` in `void (*f)(int);`
+    nested_declarator_start: Option<usize>,
+    // Positions of `(` and after `)` of the parameters of a function declared in a nested declarator,
+    // e.g. `(int)` in `int (*make(int))(double);`
+    nested_function_parameters: Option<(usize, usize)>,
+    diagnostics: Vec<Diagnostic>,
+}
 
-    fn skip_operator(&mut self, operator: &str)
+impl DeclarationFinder
+{
+    fn eof(&self) -> bool
     {
-        self.skip_exact_token(&Token::Operator(operator.into()));
+        self.pos >= self.tokens.len()
     }
 
-    fn skip_identifier(&mut self, identifier: &str)
+    /// Current token, or an empty `Token::Unknown` at the end of the file
+    fn token(&self) -> &Token
     {
-        self.skip_exact_token(&Token::Identifier(identifier.into()));
+        static EOF_TOKEN: Token = Token::Unknown(String::new());
+        self.tokens.get(self.pos).unwrap_or(&EOF_TOKEN)
     }
 
-    /// skip __attribute__(( ...... ))
-    fn skip_attribute(&mut self)
+    fn next_token(&self) -> Option<&Token>
     {
-        self.skip_identifier("__attribute__");
-        self.skip_bracket_pair("(", ")");
+        self.tokens.get(self.pos + 1)
     }
 
-    /// Skip content inside `<` and `>` (including the closing `>`)
-    fn skip_template_brackets(&mut self)
+    fn skip_token(&mut self)
     {
-        self.skip_operator("<");
+        self.pos += 1;
+    }
 
-        while !self.eof()
+    /// Prefix `name` with the names of the enclosing namespaces and classes
+    fn qualify(&self, name: &str) -> String
+    {
+        // Friends are not members of the class that declares them
+        let is_friend = self.specifiers.contains(&Specifier::Friend);
+
+        let mut res = String::new();
+        for scope in &self.scopes
         {
-            if let Token::Operator(s) = self.token()
-            {
-                match s.as_str()
-                {
-                    "(" => self.skip_bracket_pair("(", ")"),
-                    "{" => self.skip_bracket_pair("{", "}"),
-                    "[" => self.skip_bracket_pair("[", "]"),
-                    "<" => self.skip_template_brackets(),
-                    ">" => {
-                        self.skip_token();
-                        return;
-                    },
-                    _ => self.skip_token(),
-                }
-            }
-            else
+            match scope
             {
-                self.skip_token();
+                Scope::Namespace { names, .. } => {
+                    for namespace in names
+                    {
+                        res += namespace;
+                        res += "::";
+                    }
+                },
+                Scope::Class { name } if !is_friend => {
+                    res += name;
+                    res += "::";
+                },
+                _ => {},
             }
         }
+        res + name
+    }
+
+    fn is_in_class(&self) -> bool
+    {
+        matches!(self.scopes.last(), Some(Scope::Class { .. }))
+    }
 
-        panic!("skip_template_brackets: EOF");
+    fn is_in_anonymous_namespace(&self) -> bool
+    {
+        self.scopes.iter().any(|scope| matches!(scope, Scope::Namespace { anonymous: true, .. }))
     }
 
-    fn skip_to_one_of_operators(&mut self, operators: Vec<&str>) -> String
+    fn is_exported(&self) -> bool
     {
-        while !self.eof()
+        if self.is_in_class()
         {
-            if let Token::Operator(s) = &self.token()
-            {
-                if operators.contains(&&s[..])
-                {
-                    return s.into();
-                }
-                else if s == "("
-                {
-                    self.skip_token();
-                    self.skip_to_operator_inclusive(")");
+            return false;
+        }
+        self.exported || self.scopes.iter().any(|scope| matches!(scope, Scope::Export | Scope::Namespace { exported: true, .. }))
+    }
+
+    /// Language linkage of the current declaration, given by the innermost `extern "C"` or `extern "C++"`
+    fn current_language_linkage(&self) -> LanguageLinkage
+    {
+        if self.dialect == Dialect::C
+        {
+            return LanguageLinkage::C;
+        }
+        let enclosing = self.scopes.iter().rev().find_map(|scope| match scope
+        {
+            Scope::LinkageSpecification { language } => Some(*language),
+            _ => None,
+        });
+        self.language_linkage.or(enclosing).unwrap_or(LanguageLinkage::Cpp)
+    }
+
+    /// Linkage of a declaration of `kind` in the current scope, without taking its type into account
+    fn current_linkage(&self, name: &str, kind: &DeclarationKind) -> Linkage
+    {
+        let is_static = self.specifiers.contains(&Specifier::Static);
+        match kind
+        {
+            DeclarationKind::Macro | DeclarationKind::Typedef | DeclarationKind::TypeAlias | DeclarationKind::NamespaceAlias
+                | DeclarationKind::UsingDeclaration | DeclarationKind::UsingEnum | DeclarationKind::UsingDirective
+                | DeclarationKind::Concept | DeclarationKind::Module | DeclarationKind::Import => Linkage::None,
+            _ if name.starts_with("<unnamed") => Linkage::None,
+            DeclarationKind::Variable if self.is_in_class() && !is_static => Linkage::None,
+            _ if self.is_in_anonymous_namespace() => Linkage::Internal,
+            DeclarationKind::Variable | DeclarationKind::Function if is_static && !self.is_in_class() => Linkage::Internal,
+            _ => Linkage::External,
+        }
+    }
+
+    fn add_declaration(&mut self, name: &str, kind: DeclarationKind) -> &mut Declaration
+    {
+        let mut declaration = Declaration::new(&self.qualify(name), kind);
+        declaration.linkage = self.current_linkage(name, &declaration.kind);
+        if declaration.linkage == Linkage::External
+            && matches!(declaration.kind, DeclarationKind::Variable | DeclarationKind::Function)
+            && !self.is_in_class()
+        {
+            declaration.language_linkage = Some(self.current_language_linkage());
+        }
+        if declaration.kind == DeclarationKind::Variable
+        {
+            declaration.storage_duration = if self.specifiers.contains(&Specifier::ThreadLocal)
+            {
+                Some(StorageDuration::Thread)
+            }
+            else if !self.is_in_class() || self.specifiers.contains(&Specifier::Static)
+            {
+                Some(StorageDuration::Static)
+            }
+            else
+            {
+                None
+            };
+        }
+        declaration.is_exported = self.is_exported();
+        // Access specifiers do not apply to friends
+        if !self.specifiers.contains(&Specifier::Friend)
+        {
+            declaration.access = self.access;
+        }
+        declaration.specifiers = self.specifiers.clone();
+        declaration.attributes = self.attributes.clone();
+        self.declarations.push(declaration);
+        self.declarations.last_mut().unwrap()
+    }
+
+    fn add_unique_declaration(&mut self, name: &str, kind: DeclarationKind) -> Option<&mut Declaration>
+    {
+        let qualified_name = self.qualify(name);
+        if self.declarations.iter().any(|d| d.name == qualified_name)
+        {
+            return None;
+        }
+        Some(self.add_declaration(name, kind))
+    }
+}
+
+/// Filter out unneeded tokens to simplify processing
+///
+/// 1. Remove preprocessor directives
+/// 2. Remove whitespace (Token::WhiteSpace and Token::NewLine)
+/// 3. Remove comments
+/// 4. Replace identifiers according to `replacements`, an empty replacement removes the identifier
+///
+/// Returns the remaining tokens and their positions
+fn filter_tokens(input_tokens: Vec::<Token>, replacements: &BTreeMap<String, String>) -> (Vec::<Token>, Vec::<TokenPosition>)
+{
+    let mut res = Vec::<Token>::new();
+    let mut positions = Vec::<TokenPosition>::new();
+    let mut line = 1;
+    let mut after_space = false;
+    let mut is_in_preprocessor = false;
+
+    // Projects may define its macro that interfere with parsing
+    // One of examples would be codintionally use `final` keyword, e.g.:
+    //
+    // #ifdef BUILD_ON_TARGET
+    //   #define FINAL final
+    // #else // test build
+    //   #define FINAL
+    // #endif
+    //
+    // class A FINAL: class B {}
+    //
+    // They are configured with replacements, e.g. `FINAL` -> `final` or `MYLIB_API` -> ``
+    let replacements: BTreeMap<&str, Vec<Token>> = replacements
+        .iter()
+        .map(|(identifier, replacement)| (identifier.as_str(), tokenize(replacement)
+            .into_iter()
+            .filter(|t| !matches!(t, Token::WhiteSpace(_) | Token::NewLine(_) | Token::Comment(_)))
+            .collect()))
+        .collect();
+
+    for token in input_tokens.into_iter()
+    {
+        let token_line = line;
+        line += match &token
+        {
+            Token::Unknown(s) | Token::LineContinuation(s) | Token::NewLine(s) | Token::WhiteSpace(s) | Token::Comment(s)
+                | Token::String(s) | Token::Char(s) | Token::Number(s) | Token::Operator(s) | Token::Identifier(s) => s.matches('\n').count(),
+        };
+
+        if is_in_preprocessor
+        {
+            if let Token::NewLine(_) = token
+            {
+                is_in_preprocessor = false;
+            }
+            continue;
+        }
+
+        let position = TokenPosition { line: token_line, after_space };
+        after_space = true;
+
+        if matches!(token, Token::WhiteSpace(_) | Token::NewLine(_) | Token::Comment(_))
+        {
+            // skip
+        }
+        else if let Token::Operator(s) = token
+        {
+            if s == "#"
+            {
+                is_in_preprocessor = true;
+            }
+            else
+            {
+                res.push(Token::Operator(s));
+                positions.push(position);
+                after_space = false;
+            }
+        }
+        else if let Token::Identifier(ref s) = token
+        {
+            if let Some(replacement) = replacements.get(s.as_str())
+            {
+                // Replacements are not replaced again
+                for t in replacement
+                {
+                    res.push(t.clone());
+                    positions.push(TokenPosition { line: token_line, after_space });
+                    after_space = false;
+                }
+                continue;
+            }
+
+            res.push(token);
+            positions.push(position);
+            after_space = false;
+        }
+        else
+        {
+            res.push(token);
+            positions.push(position);
+            after_space = false;
+        }
+    }
+
+    (res, positions)
+}
+
+/// Extend a name being read, e.g. `Foo` with `bar` after `::`, or start a new name
+fn append_name_component(name: &str, component: &str, after_scope_operator: bool) -> String
+{
+    if after_scope_operator && !name.is_empty()
+    {
+        format!("{}::{}", name, component)
+    }
+    else
+    {
+        component.into()
+    }
+}
+
+fn is_operator_name(name: &str) -> bool
+{
+    match name.strip_prefix("operator")
+    {
+        Some(rest) => rest.starts_with(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_'),
+        None => false,
+    }
+}
+
+/// Split a qualified name into a qualifier and an unqualified name
+///
+/// E.g. `a::B::operator std::string` is split into `a::B` and `operator std::string`.
+/// The qualifier is empty for unqualified names.
+pub fn split_qualified_name(name: &str) -> (&str, &str)
+{
+    let mut start = 0;
+    let mut split = None;
+
+    while let Some(offset) = name[start..].find("::")
+    {
+        if is_operator_name(&name[start..])
+        {
+            break;
+        }
+        split = Some(start + offset);
+        start += offset + 2;
+    }
+
+    match split
+    {
+        Some(pos) => (&name[..pos], &name[pos + 2..]),
+        None => ("", name),
+    }
+}
+
+/// Join tokens into a single string
+///
+/// A space is inserted only between tokens that would merge otherwise, e.g. `unsigned int`,
+/// and where it improves readability, e.g. `std::map<int, int>` or `char* const`
+fn join_tokens(tokens: &[Token]) -> String
+{
+    let mut res = String::new();
+    let mut previous: Option<&str> = None;
+    let mut previous_is_word = false;
+
+    for token in tokens
+    {
+        let (text, is_word) = match token
+        {
+            Token::Identifier(s) | Token::Number(s) => (s, true),
+            Token::Operator(s) | Token::String(s) | Token::Char(s) | Token::Unknown(s) => (s, false),
+            Token::WhiteSpace(_) | Token::NewLine(_) | Token::LineContinuation(_) | Token::Comment(_) => continue,
+        };
+
+        let needs_space = match previous
+        {
+            Some(_) if previous_is_word && is_word => true,
+            Some(",") => true,
+            Some(">") | Some(")") => is_word,
+            Some("*") | Some("&") => text == "const" || text == "volatile",
+            _ => false,
+        };
+        if needs_space
+        {
+            res.push(' ');
+        }
+        res += text;
+        previous = Some(text);
+        previous_is_word = is_word;
+    }
+
+    res
+}
+
+/// Check if tokens start with an attribute, e.g. `[[nodiscard]]`, `__attribute__((packed))`,
+/// `__declspec(dllexport)` or `alignas(16)`
+fn is_attribute_start(tokens: &[Token]) -> bool
+{
+    match tokens
+    {
+        [Token::Identifier(s), ..] => matches!(s.as_str(), "__attribute__" | "__declspec" | "alignas"),
+        [Token::Operator(s1), Token::Operator(s2), ..] => s1 == "[" && s2 == "[",
+        _ => false,
+    }
+}
+
+/// Remove specifiers and attributes that are not a part of a type, e.g. `static` or `[[maybe_unused]]`
+fn strip_declaration_specifiers(tokens: &[Token]) -> Vec<Token>
+{
+    let mut res = Vec::<Token>::new();
+    let mut attribute_depth = 0;
+    let mut is_in_attribute = false;
+
+    for (i, token) in tokens.iter().enumerate()
+    {
+        if !is_in_attribute && (is_attribute_start(&tokens[i..]) || is_asm_label_start(&tokens[i..]))
+        {
+            is_in_attribute = true;
+        }
+
+        if is_in_attribute
+        {
+            // Skip up to the bracket that closes the attribute
+            match token
+            {
+                Token::Operator(s) if s == "(" || s == "[" => attribute_depth += 1,
+                Token::Operator(s) if s == ")" || s == "]" => {
+                    attribute_depth -= 1;
+                    is_in_attribute = attribute_depth > 0;
+                },
+                _ => {},
+            }
+            continue;
+        }
+
+        if let Token::Identifier(s) = token
+        {
+            match s.as_str()
+            {
+                "virtual" | "static" | "inline" | "constexpr" | "consteval" | "constinit" | "explicit" | "extern"
+                    | "friend" | "mutable" | "thread_local" | "_Thread_local" | "__thread" | "register" => continue,
+                _ => {},
+            }
+        }
+        res.push(token.clone());
+    }
+
+    res
+}
+
+/// Split the type in front of the first declarator into the part shared by all declarators and
+/// the part of the first declarator only, e.g. `const char` and `*` for `const char *a, b;`
+fn split_declarator_type(tokens: &[Token]) -> (Vec<Token>, Vec<Token>)
+{
+    let mut depth = 0;
+    // Parentheses of a type specifier, e.g. `decltype(x)`
+    let mut parentheses_depth = 0;
+    for (i, token) in tokens.iter().enumerate()
+    {
+        match token
+        {
+            Token::Operator(s) if s == "<" => depth += 1,
+            Token::Operator(s) if s == ">" => depth -= 1,
+            Token::Operator(s) if s == "(" && (parentheses_depth > 0 || i > 0 && is_typeof_specifier(&tokens[i - 1])) => {
+                parentheses_depth += 1;
+            },
+            Token::Operator(s) if s == ")" && parentheses_depth > 0 => parentheses_depth -= 1,
+            Token::Operator(s) if matches!(s.as_str(), "*" | "&" | "(") && depth == 0 && parentheses_depth == 0 => {
+                return (tokens[..i].to_vec(), tokens[i..].to_vec());
+            },
+            _ => {},
+        }
+    }
+    (tokens.to_vec(), Vec::new())
+}
+
+/// Check if a token starts a type specifier followed by an expression or a type in parentheses,
+/// e.g. `decltype` in `decltype(x)`, or `typeof` in `typeof(int)`
+fn is_typeof_specifier(token: &Token) -> bool
+{
+    matches!(token, Token::Identifier(s) if matches!(s.as_str(), "decltype" | "typeof" | "typeof_unqual" | "__typeof__" | "__typeof"))
+}
+
+/// Check if a type is `const` itself, e.g. `const int` or `char* const`, but not `const char*`
+fn is_const_type(type_name: &str) -> bool
+{
+    let outermost = match type_name.rfind(['*', '&', '('])
+    {
+        Some(pos) => &type_name[pos..],
+        None => type_name,
+    };
+    outermost.split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_').any(|word| word == "const")
+}
+
+/// Check if an identifier is a C++ keyword that starts a declaration, but is an ordinary identifier in C
+fn is_cpp_only_keyword(identifier: &str) -> bool
+{
+    matches!(identifier, "template" | "using" | "concept" | "export" | "module" | "import" | "class" | "namespace" | "friend")
+}
+
+/// Check if tokens start with an asm label, e.g. `__asm__("real_name")` in `int x __asm__("real_name");`
+fn is_asm_label_start(tokens: &[Token]) -> bool
+{
+    matches!(tokens, [Token::Identifier(s), Token::Operator(bracket), ..] if matches!(s.as_str(), "asm" | "__asm" | "__asm__") && bracket == "(")
+}
+
+/// Check if an identifier can only be a part of a type, so it is not a parameter name
+fn is_type_keyword(identifier: &str) -> bool
+{
+    matches!(identifier, "void" | "bool" | "char" | "wchar_t" | "char8_t" | "char16_t" | "char32_t"
+        | "short" | "int" | "long" | "float" | "double" | "signed" | "unsigned" | "auto"
+        | "const" | "volatile" | "typename" | "class" | "struct" | "union" | "enum")
+}
+
+/// Find the first `=` that is not nested in brackets, e.g. in `std::enable_if_t<N == 1>* = nullptr`
+fn find_default_value_separator(tokens: &[Token]) -> Option<usize>
+{
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate()
+    {
+        if let Token::Operator(s) = token
+        {
+            match s.as_str()
+            {
+                "(" | "[" | "{" | "<" => depth += 1,
+                ")" | "]" | "}" | ">" => depth -= 1,
+                "=" if depth == 0 => return Some(i),
+                _ => {},
+            }
+        }
+    }
+
+    None
+}
+
+/// Parse tokens of a single parameter, e.g. `const std::string& s = "abc"` or `int a[10]`
+///
+/// In template parameters `class T` declares `T`, while in function parameters `struct S` is an unnamed parameter.
+fn parse_parameter(tokens: &[Token], is_template_parameter: bool) -> Parameter
+{
+    let (declaration, default_value) = match find_default_value_separator(tokens)
+    {
+        Some(pos) => (&tokens[..pos], Some(join_tokens(&tokens[pos + 1..]))),
+        None => (tokens, None),
+    };
+
+    // Array brackets follow the name, e.g. `int a[10]`
+    let mut type_end = declaration.len();
+    while type_end > 0 && declaration[type_end - 1] == Token::Operator("]".into())
+    {
+        match declaration[..type_end].iter().rposition(|t| *t == Token::Operator("[".into()))
+        {
+            Some(pos) => type_end = pos,
+            None => break,
+        }
+    }
+    let array_brackets = &declaration[type_end..];
+
+    // The name is the last identifier, unless it is a part of the type, e.g. `unsigned int`, `struct S` or `std::string`
+    let mut name = None;
+    if type_end >= 2
+        && let Token::Identifier(s) = &declaration[type_end - 1]
+        && !is_type_keyword(s)
+        && (is_template_parameter || !matches!(&declaration[type_end - 2], Token::Identifier(previous) if matches!(previous.as_str(), "struct" | "class" | "union" | "enum" | "typename")))
+        && declaration[type_end - 2] != Token::Operator(":".into())
+    {
+        name = Some(s.clone());
+        type_end -= 1;
+    }
+
+    let mut type_tokens = declaration[..type_end].to_vec();
+    type_tokens.extend_from_slice(array_brackets);
+
+    Parameter {
+        type_name: join_tokens(&type_tokens),
+        name,
+        default_value,
+    }
+}
+
+impl DeclarationFinder
+{
+    #[allow(dead_code)]
+    fn dump(&self)
+    {
+        for (i, token) in self.tokens.iter().enumerate()
+        {
+            let marker = if self.pos == i {" <--------------------------"} else {""};
+            println!("{} {:?} {}", i, token, marker);
+        }
+    }
+
+    fn is_identifier(&self, value: &str) -> bool
+    {
+        !self.eof() && *self.token() == Token::Identifier(value.into())
+    }
+
+    fn is_operator(&self, value: &str) -> bool
+    {
+        !self.eof() && *self.token() == Token::Operator(value.into())
+    }
+
+    fn assert_not_eof(&self, error_msg: &str) -> Result<(), String>
+    {
+        if self.eof()
+        {
+            return Err(error_msg.into());
+        }
+        Ok(())
+    }
+
+    fn skip_exact_token(&mut self, token_to_skip: &Token) -> Result<(), String>
+    {
+        if self.eof()
+        {
+            return Err(format!("expected `{}`, found end of file", token_to_skip));
+        }
+        if self.token() != token_to_skip
+        {
+            return Err(format!("expected `{}`, found {}", token_to_skip, describe_token(self.token())));
+        }
+        self.skip_token();
+        Ok(())
+    }
+
+    fn skip_operator(&mut self, operator: &str) -> Result<(), String>
+    {
+        self.skip_exact_token(&Token::Operator(operator.into()))
+    }
+
+    fn skip_identifier(&mut self, identifier: &str) -> Result<(), String>
+    {
+        self.skip_exact_token(&Token::Identifier(identifier.into()))
+    }
+
+    /// Text of tokens from `start` to `end` (exclusive) with whitespace between them normalised to single spaces,
+    /// e.g. `1 << 3`
+    fn source_text(&self, start: usize, end: usize) -> String
+    {
+        let mut res = String::new();
+        for i in start..end
+        {
+            if i != start && self.positions[i].after_space
+            {
+                res.push(' ');
+            }
+            res += match &self.tokens[i]
+            {
+                Token::Unknown(s) | Token::LineContinuation(s) | Token::NewLine(s) | Token::WhiteSpace(s) | Token::Comment(s)
+                    | Token::String(s) | Token::Char(s) | Token::Number(s) | Token::Operator(s) | Token::Identifier(s) => s,
+            };
+        }
+        res
+    }
+
+    fn is_attribute_start(&self) -> bool
+    {
+        is_attribute_start(self.tokens.get(self.pos..).unwrap_or_default())
+    }
+
+    /// Read the text inside a pair of brackets, e.g. `"message"` from `("message")`
+    fn read_bracket_content(&mut self, opening_bracket: &str, closing_bracket: &str) -> Result<String, String>
+    {
+        let start = self.pos;
+        self.skip_bracket_pair(opening_bracket, closing_bracket)?;
+        Ok(join_tokens(&self.tokens[start + 1..self.pos - 1]))
+    }
+
+    /// Read attributes up to `closing_bracket`, e.g. `nodiscard, gnu::always_inline` or `aligned(16) packed`
+    ///
+    /// `namespace` comes from `[[using ns: ...]]`
+    fn read_attribute_list(&mut self, syntax: AttributeSyntax, closing_bracket: &str, namespace: &str) -> Result<(), String>
+    {
+        loop
+        {
+            match self.token().clone()
+            {
+                Token::Operator(s) if s == closing_bracket => return Ok(()),
+                Token::Operator(s) if s == "," => self.skip_token(),
+                // Pack expansion, e.g. `[[attributes...]]`
+                Token::Operator(s) if s == "." => self.skip_token(),
+                Token::Identifier(s) => {
+                    self.skip_token();
+                    let mut name = if namespace.is_empty() { s } else { format!("{}::{}", namespace, s) };
+                    while self.is_operator(":") && self.next_token() == Some(&Token::Operator(":".into()))
+                    {
+                        self.skip_operator(":")?;
+                        self.skip_operator(":")?;
+                        match self.token().clone()
+                        {
+                            Token::Identifier(s) => name = format!("{}::{}", name, s),
+                            token => return Err(format!("expected an identifier after `::` in an attribute, found {}", describe_token(&token))),
+                        }
+                        self.skip_token();
+                    }
+                    let arguments = if self.is_operator("(") { Some(self.read_bracket_content("(", ")")?) } else { None };
+                    self.attributes.push(Attribute { syntax: syntax.clone(), name, arguments });
+                },
+                token => return Err(format!("expected an attribute, found {}", describe_token(&token))),
+            }
+        }
+    }
+
+    /// Read an attribute in any syntax:
+    ///
+    /// [[nodiscard, deprecated("use g")]]
+    /// [[using gnu: always_inline, hot]]
+    /// __attribute__((packed, aligned(16)))
+    /// __declspec(dllexport)
+    /// alignas(16)
+    fn read_attribute(&mut self) -> Result<(), String>
+    {
+        if self.is_identifier("alignas")
+        {
+            self.skip_token();
+            let arguments = self.read_bracket_content("(", ")")?;
+            self.attributes.push(Attribute { syntax: AttributeSyntax::Alignas, name: "alignas".into(), arguments: Some(arguments) });
+            return Ok(());
+        }
+
+        if self.is_identifier("__attribute__")
+        {
+            self.skip_token();
+            self.skip_operator("(")?;
+            self.skip_operator("(")?;
+            self.read_attribute_list(AttributeSyntax::Gnu, ")", "")?;
+            self.skip_operator(")")?;
+            return self.skip_operator(")");
+        }
+
+        if self.is_identifier("__declspec")
+        {
+            self.skip_token();
+            self.skip_operator("(")?;
+            self.read_attribute_list(AttributeSyntax::Declspec, ")", "")?;
+            return self.skip_operator(")");
+        }
+
+        self.skip_operator("[")?;
+        self.skip_operator("[")?;
+        let mut namespace = String::new();
+        if self.is_identifier("using")
+        {
+            self.skip_token();
+            if let Token::Identifier(s) = self.token()
+            {
+                namespace = s.clone();
+            }
+            self.skip_token();
+            self.skip_operator(":")?;
+        }
+        self.read_attribute_list(AttributeSyntax::Standard, "]", &namespace)?;
+        self.skip_operator("]")?;
+        self.skip_operator("]")
+    }
+
+    /// Skip content inside `<` and `>` (including the closing `>`)
+    fn skip_template_brackets(&mut self) -> Result<(), String>
+    {
+        self.skip_operator("<")?;
+
+        while !self.eof()
+        {
+            if let Token::Operator(s) = self.token()
+            {
+                match s.as_str()
+                {
+                    "(" => self.skip_bracket_pair("(", ")")?,
+                    "{" => self.skip_bracket_pair("{", "}")?,
+                    "[" => self.skip_bracket_pair("[", "]")?,
+                    "<" => self.skip_template_brackets()?,
+                    ">" => {
+                        self.skip_token();
+                        return Ok(());
+                    },
+                    _ => self.skip_token(),
+                }
+            }
+            else
+            {
+                self.skip_token();
+            }
+        }
+
+        Err("expected `>`, found end of file".into())
+    }
+
+    fn skip_to_one_of_operators(&mut self, operators: Vec<&str>) -> Result<String, String>
+    {
+        while !self.eof()
+        {
+            if let Token::Operator(s) = &self.token()
+            {
+                if operators.contains(&&s[..])
+                {
+                    return Ok(s.into());
+                }
+                else if s == "("
+                {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive(")")?;
+                }
+                else if s == "{"
+                {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive("}")?;
+                }
+                else if s == "["
+                {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive("]")?;
+                }
+                else
+                {
+                    self.skip_token();
+                }
+            }
+            else
+            {
+                self.skip_token();
+            }
+        }
+
+        Err(format!(
+            "expected {}, found end of file",
+            operators.iter().map(|operator| format!("`{}`", operator)).collect::<Vec<_>>().join(" or ")
+        ))
+    }
+
+    /// Check if `<` at the pointer opens template arguments, e.g. `std::pair<int, int>{}`,
+    /// and not a comparison, e.g. `a < b, c`
+    fn is_template_arguments_start(&self) -> bool
+    {
+        if !matches!(self.tokens.get(self.pos.wrapping_sub(1)), Some(Token::Identifier(_)))
+        {
+            return false;
+        }
+
+        let operator_at = |i: usize| match self.tokens.get(i)
+        {
+            Some(Token::Operator(s)) => s.as_str(),
+            _ => "",
+        };
+
+        let mut depth = 0;
+        let mut brackets = Vec::new();
+        for i in self.pos..self.tokens.len()
+        {
+            let s = operator_at(i);
+            match s
+            {
+                "(" | "[" => brackets.push(s),
+                ")" | "]" if brackets.pop().is_none() => return false,
+                ")" | "]" => {},
+                "<" if brackets.is_empty() => depth += 1,
+                ">" if brackets.is_empty() => {
+                    depth -= 1;
+                    if depth == 0
+                    {
+                        return true;
+                    }
+                },
+                ";" | "{" | "}" => return false,
+                // Logical operators and assignments of the next declarator, e.g. `a < b, c = d > e`
+                "&" | "|" if operator_at(i + 1) == s => return false,
+                "=" if brackets.is_empty() && operator_at(i + 1) != "=" && !matches!(operator_at(i - 1), "=" | "!" | "<" | ">") => return false,
+                _ => {},
+            }
+        }
+        false
+    }
+
+    /// Skip a lambda expression, e.g. `[&, x = 1]<typename T>(T t) mutable -> int { ... }`
+    ///
+    /// The pointer is at `[`.
+    fn skip_lambda(&mut self) -> Result<(), String>
+    {
+        self.skip_bracket_pair("[", "]")?;
+        if self.is_operator("<")
+        {
+            self.skip_template_brackets()?;
+        }
+        while !self.is_operator("{")
+        {
+            self.assert_not_eof("expected the lambda body, found end of file")?;
+            match self.token()
+            {
+                Token::Operator(s) if s == "(" => self.skip_bracket_pair("(", ")")?,
+                Token::Operator(s) if s == "<" => self.skip_template_brackets()?,
+                Token::Operator(s) if s == "[" => self.skip_bracket_pair("[", "]")?,
+                Token::Operator(s) if s == ";" || s == "}" => return Err(format!("expected the lambda body, found `{}`", s)),
+                _ => self.skip_token(),
+            }
+        }
+        self.skip_bracket_pair("{", "}")
+    }
+
+    /// Skip an initialiser or a bit field width up to `,` or `;` separating declarators
+    ///
+    /// Brackets, braced initialisers, template arguments and lambdas are balanced.
+    /// Returns true if the whole initialiser is a lambda expression.
+    fn skip_initializer(&mut self) -> Result<bool, String>
+    {
+        let start = self.pos;
+        let mut is_lambda = false;
+        loop
+        {
+            self.assert_not_eof("expected `,` or `;`, found end of file")?;
+            match self.token()
+            {
+                Token::Operator(s) if s == "," || s == ";" => return Ok(is_lambda),
+                Token::Operator(s) if s == "[" => {
+                    let is_first = self.pos == start;
+                    is_lambda = self.skip_lambda_or_subscript()? && is_first;
+                    continue;
+                },
+                Token::Operator(s) if s == "(" => self.skip_bracket_pair("(", ")")?,
+                Token::Operator(s) if s == "{" => self.skip_bracket_pair("{", "}")?,
+                Token::Operator(s) if s == "<" && self.is_template_arguments_start() => self.skip_template_brackets()?,
+                _ => self.skip_token(),
+            }
+            is_lambda = false;
+        }
+    }
+
+    /// Skip a lambda expression or a subscript, e.g. `[] { ... }` or `[i + 1]`
+    ///
+    /// Returns true for a lambda expression.
+    fn skip_lambda_or_subscript(&mut self) -> Result<bool, String>
+    {
+        let start = self.pos;
+        self.skip_bracket_pair("[", "]")?;
+        if matches!(self.token(), Token::Operator(s) if matches!(s.as_str(), "(" | "{" | "<"))
+            || matches!(self.token(), Token::Identifier(s) if matches!(s.as_str(), "mutable" | "constexpr" | "consteval" | "noexcept"))
+        {
+            self.pos = start;
+            self.skip_lambda()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn skip_to_operator_inclusive(&mut self, operator: &str) -> Result<(), String>
+    {
+        while !self.eof()
+        {
+            if let Token::Operator(s) = &self.token()
+            {
+                if s == operator
+                {
+                    self.skip_token();
+                    return Ok(());
+                }
+                else if s == "("
+                {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive(")")?;
+                }
+                else if s == "{"
+                {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive("}")?;
+                }
+                else if s == "["
+                {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive("]")?;
+                }
+                else
+                {
+                    self.skip_token();
+                }
+            }
+            else
+            {
+                self.skip_token();
+            }
+        }
+
+        Err(format!("expected `{}`, found end of file", operator))
+    }
+
+    fn skip_bracket_pair(&mut self, opening_bracket: &str, closing_bracket: &str) -> Result<(), String>
+    {
+        if self.eof()
+        {
+            return Err(format!("expected `{}`, found end of file", opening_bracket));
+        }
+
+        self.skip_token();
+
+        while !self.eof()
+        {
+            let token = self.token();
+
+            if *token == Token::Operator(closing_bracket.into())
+            {
+                self.skip_token();
+                return Ok(());
+            }
+            else if *token == Token::Operator("(".into())
+            {
+                self.skip_bracket_pair("(", ")")?;
+            }
+            else if *token == Token::Operator("{".into())
+            {
+                self.skip_bracket_pair("{", "}")?;
+            }
+            else if *token == Token::Operator("[".into())
+            {
+                self.skip_bracket_pair("[", "]")?;
+            }
+            else
+            {
+                self.skip_token();
+            }
+        }
+
+        Err(format!("expected `{}`, found end of file", closing_bracket))
+    }
+
+    /// Read a function parameter list in `(` and `)` or a template parameter list in `<` and `>`
+    ///
+    /// Returns tokens of each parameter. The pointer is moved past the closing bracket.
+    fn read_parameter_list(&mut self, opening_bracket: &str, closing_bracket: &str) -> Result<Vec<Vec<Token>>, String>
+    {
+        self.skip_operator(opening_bracket)?;
+
+        let mut parameters = Vec::<Vec<Token>>::new();
+        let mut parameter = Vec::<Token>::new();
+        // Template arguments in parameter types, e.g. `std::map<int, int>`
+        let mut angle_bracket_depth = 0;
+        // `<` in a default argument of a function is more likely a comparison, e.g. `int x = a < b`
+        let mut is_in_default_value = false;
+
+        while !self.eof()
+        {
+            match self.token().clone()
+            {
+                Token::Operator(s) if s == closing_bracket && angle_bracket_depth == 0 => {
+                    self.skip_token();
+                    if !parameter.is_empty() || !parameters.is_empty()
+                    {
+                        parameters.push(parameter);
+                    }
+                    return Ok(parameters);
+                },
+                Token::Operator(s) if s == "," && angle_bracket_depth == 0 => {
+                    self.skip_token();
+                    parameters.push(std::mem::take(&mut parameter));
+                    is_in_default_value = false;
+                },
+                Token::Operator(s) if s == "(" || s == "[" || s == "{" => {
+                    let start = self.pos;
+                    match s.as_str()
+                    {
+                        "(" => self.skip_bracket_pair("(", ")")?,
+                        "[" => self.skip_bracket_pair("[", "]")?,
+                        _ => self.skip_bracket_pair("{", "}")?,
+                    }
+                    parameter.extend_from_slice(&self.tokens[start..self.pos]);
+                },
+                token => {
+                    match &token
+                    {
+                        Token::Operator(s) if s == "<" && (closing_bracket == ">" || !is_in_default_value) => angle_bracket_depth += 1,
+                        Token::Operator(s) if s == ">" && angle_bracket_depth > 0 => angle_bracket_depth -= 1,
+                        Token::Operator(s) if s == "=" && angle_bracket_depth == 0 => is_in_default_value = true,
+                        _ => {},
+                    }
+                    parameter.push(token);
+                    self.skip_token();
+                },
+            }
+        }
+
+        Err(format!("expected `{}`, found end of file", closing_bracket))
+    }
+
+    /// Read parameter declarations of a K&R function definition, e.g. `int a; char *b, c;`
+    /// in `int f(a, b, c) int a; char *b, c; { ... }`
+    ///
+    /// Parameters without a declaration are `int`.
+    fn read_kr_parameter_declarations(&mut self, signature: &mut FunctionSignature) -> Result<(), String>
+    {
+        let mut declared = Vec::<Parameter>::new();
+        while !self.is_operator("{")
+        {
+            self.assert_not_eof("expected `{`, found end of file")?;
+
+            // The type shared by all declarators, e.g. `char` in `char *b, c;`
+            let first = strip_declaration_specifiers(&self.read_to_separator()?);
+            let parameter = parse_parameter(&first, false);
+            let base_end = first
+                .iter()
+                .position(|t| matches!(t, Token::Operator(s) if matches!(s.as_str(), "*" | "&" | "(" | "[")))
+                .or_else(|| first.iter().rposition(|t| Some(t) == parameter.name.clone().map(Token::Identifier).as_ref()))
+                .unwrap_or(first.len());
+            let base_type = first[..base_end].to_vec();
+            declared.push(parameter);
+
+            while self.is_operator(",")
+            {
+                self.skip_operator(",")?;
+                let declarator = self.read_to_separator()?;
+                declared.push(parse_parameter(&[base_type.clone(), declarator].concat(), false));
+            }
+            self.skip_operator(";")?;
+        }
+
+        for parameter in &mut signature.parameters
+        {
+            // A parameter of a K&R definition is read as a type, e.g. `a` in `f(a, b)`
+            if parameter.name.is_some()
+            {
+                continue;
+            }
+            let name = std::mem::take(&mut parameter.type_name);
+            *parameter = match declared.iter().find(|d| d.name.as_ref() == Some(&name))
+            {
+                Some(declaration) => declaration.clone(),
+                None => Parameter { type_name: "int".into(), name: Some(name), default_value: None },
+            };
+        }
+        Ok(())
+    }
+
+    /// Read function parameters in `(` and `)` into `signature`
+    fn read_function_parameters(&mut self, signature: &mut FunctionSignature) -> Result<(), String>
+    {
+        for parameter in self.read_parameter_list("(", ")")?
+        {
+            // Attributes of parameters are not kept, e.g. `[[maybe_unused]]`
+            let parameter = strip_declaration_specifiers(&parameter);
+            if parameter.iter().all(|t| *t == Token::Operator(".".into()))
+            {
+                signature.is_variadic = true;
+            }
+            else if parameter != [Token::Identifier("void".into())]
+            {
+                signature.parameters.push(parse_parameter(&parameter, false));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read parameters and everything that follows them, including the function body
+    ///
+    /// Returns true for function definitions, including `= default` and `= delete`
+    fn read_function_signature(&mut self, signature: &mut FunctionSignature) -> Result<bool, String>
+    {
+        // To handle function declarations and definitions, e.g.:
+        //
+        // int myFunc ( ... );
+        // int myFunc ( ... ) { ... }
+        // virtual int myFunc ( ... ) const override = 0;
+        // MyClass ( ... ) : a(1), b{2} { ... }
+        // auto myFunc ( ... ) -> int { ... }
+        // MyClass ( ... ) try : a(1) { ... } catch ( ... ) { ... }
+        //
+        // The pointer is at the "("
+
+        // 1. Check that we point at "("
+
+        if self.eof()
+        {
+            return Err("expected `(`, found end of file".into());
+        }
+
+        if *self.token() != Token::Operator("(".into())
+        {
+            return Err(format!("expected `(`, found {}", describe_token(self.token())));
+        }
+
+        // 2. Read parameter list (round brackets)
+
+        self.read_function_parameters(signature)?;
+        if self.dialect == Dialect::C
+            && matches!(self.token(), Token::Identifier(_))
+            && !self.is_attribute_start()
+            && !is_asm_label_start(&self.tokens[self.pos..])
+        {
+            self.read_kr_parameter_declarations(signature)?;
+        }
+
+        // 3. Read everything that may follow the parameter list,
+        //    and stop after ';' (declaration) or '}' (definition)
+
+        let mut is_definition = false;
+        let mut trailing_return_type: Option<Vec<Token>> = None;
+        let mut is_function_try_block = false;
+
+        loop
+        {
+            self.assert_not_eof("expected `;` or `{`, found end of file")?;
+
+            if self.is_attribute_start()
+            {
+                self.read_attribute()?;
+                continue;
+            }
+
+            if let Some(return_type) = &mut trailing_return_type
+            {
+                // Collect the trailing return type until a token that can't be a part of it
+                let start = self.pos;
+                match self.token().clone()
+                {
+                    Token::Operator(s) if s == "<" => self.skip_template_brackets()?,
+                    Token::Operator(s) if s == "(" => self.skip_bracket_pair("(", ")")?,
+                    Token::Operator(s) if s == "[" => self.skip_bracket_pair("[", "]")?,
+                    Token::Operator(s) if s == ":" && self.next_token() == Some(&Token::Operator(":".into())) => self.pos += 2,
+                    Token::Operator(s) if matches!(s.as_str(), "*" | "&" | ".") => self.skip_token(),
+                    Token::Identifier(s) if !matches!(s.as_str(), "override" | "final" | "requires" | "try") => self.skip_token(),
+                    _ => {},
+                }
+                if self.pos != start
+                {
+                    return_type.extend_from_slice(&self.tokens[start..self.pos]);
+                    continue;
+                }
+            }
+
+            match self.token().clone()
+            {
+                Token::Identifier(s) => match s.as_str()
+                {
+                    "override" => {
+                        self.skip_token();
+                        self.specifiers.push(Specifier::Override);
+                    },
+                    "const" => {
+                        self.skip_token();
+                        signature.is_const = true;
+                    },
+                    "volatile" => {
+                        self.skip_token();
+                        signature.is_volatile = true;
+                    },
+                    "noexcept" => {
+                        // noexcept, noexcept(expr)
+                        let start = self.pos;
+                        self.skip_token();
+                        if self.is_operator("(")
+                        {
+                            self.skip_bracket_pair("(", ")")?;
+                        }
+                        signature.noexcept = Some(join_tokens(&self.tokens[start..self.pos]));
+                    },
+                    "requires" => {
+                        // void f(T t) requires std::integral<T>;
+                        let clause = self.read_requires_clause()?;
+                        signature.requires_clause = Some(match signature.requires_clause.take()
+                        {
+                            Some(previous) => format!("({}) && ({})", previous, clause),
+                            None => clause,
+                        });
+                    },
+                    "throw" => {
+                        // throw(), throw(std::bad_alloc)
+                        self.skip_token();
+                        if self.is_operator("(")
+                        {
+                            self.skip_bracket_pair("(", ")")?;
+                        }
+                    },
+                    "try" => {
+                        // Function-try-block, e.g. `void f() try { ... } catch (...) { ... }`
+                        self.skip_token();
+                        is_function_try_block = true;
+                    },
+                    _ => self.skip_token(),
+                },
+                Token::Operator(s) => match s.as_str()
+                {
+                    ";" => {
+                        // 4A. It is a function declaration. Skip ";"
+                        self.skip_token();
+                        break;
+                    },
+                    "{" => {
+                        // 4B. It is a function definition. Skip curly brackets
+                        self.skip_bracket_pair("{", "}")?;
+                        if is_function_try_block
+                        {
+                            self.skip_catch_handlers()?;
+                        }
+                        is_definition = true;
+                        break;
+                    },
+                    "=" => {
+                        // = default; = delete; = 0;
+                        self.skip_token();
+                        self.assert_not_eof("expected `default`, `delete` or `0` after `=`, found end of file")?;
+                        match self.token()
+                        {
+                            Token::Identifier(s) if s == "default" => {
+                                self.specifiers.push(Specifier::Default);
+                                is_definition = true;
+                            },
+                            Token::Identifier(s) if s == "delete" => {
+                                self.specifiers.push(Specifier::Delete);
+                                is_definition = true;
+                            },
+                            Token::Number(s) if s == "0" => self.specifiers.push(Specifier::PureVirtual),
+                            token => return Err(format!("expected `default`, `delete` or `0` after `=`, found {}", describe_token(token))),
+                        }
+                        self.skip_token();
+                    },
+                    "-" if self.next_token() == Some(&Token::Operator(">".into())) => {
+                        // Trailing return type `-> T`
+                        self.skip_operator("-")?;
+                        self.skip_operator(">")?;
+                        trailing_return_type = Some(Vec::new());
+                    },
+                    ":" if self.next_token() == Some(&Token::Operator(":".into())) => {
+                        self.skip_operator(":")?;
+                        self.skip_operator(":")?;
+                    },
+                    ":" => self.skip_constructor_initializers()?,
+                    "<" => self.skip_template_brackets()?,
+                    "(" => self.skip_bracket_pair("(", ")")?,
+                    "[" => self.skip_bracket_pair("[", "]")?,
+                    "-" | ">" | "*" => self.skip_token(),
+                    "&" => {
+                        // Reference qualifiers `&` and `&&`
+                        self.skip_token();
+                        signature.ref_qualifier.get_or_insert_default().push('&');
+                    },
+                    _ => return Err(format!("expected `;` or `{{`, found {}", describe_token(self.token()))),
+                },
+                token => return Err(format!("expected `;` or `{{`, found {}", describe_token(&token))),
+            }
+        }
+
+        if let Some(return_type) = trailing_return_type
+        {
+            signature.return_type = join_tokens(&return_type);
+        }
+
+        Ok(is_definition)
+    }
+
+    /// Skip the handlers of a function-try-block, e.g. `catch (const E& e) { ... } catch (...) { ... }`
+    fn skip_catch_handlers(&mut self) -> Result<(), String>
+    {
+        while self.is_identifier("catch")
+        {
+            self.skip_token();
+            if !self.is_operator("(")
+            {
+                return Err(format!("expected `(` after `catch`, found {}", describe_token(self.token())));
+            }
+            self.skip_bracket_pair("(", ")")?;
+            if !self.is_operator("{")
+            {
+                return Err(format!("expected the handler body, found {}", describe_token(self.token())));
+            }
+            self.skip_bracket_pair("{", "}")?;
+        }
+        Ok(())
+    }
+
+    /// Skip constructor member initializer list, e.g. `: a(1), b{2}, Base<T>(3)`
+    ///
+    /// Stop at the `{` of the constructor body
+    fn skip_constructor_initializers(&mut self) -> Result<(), String>
+    {
+        self.skip_operator(":")?;
+
+        while !self.eof()
+        {
+            match self.token().clone()
+            {
+                Token::Operator(s) => match s.as_str()
+                {
+                    "(" => self.skip_bracket_pair("(", ")")?,
+                    "{" => {
+                        // Either an initializer `b{2}`, `Base<T>{3}` or the constructor body
+                        let previous = &self.tokens[self.pos - 1];
+                        if matches!(previous, Token::Identifier(_)) || *previous == Token::Operator(">".into())
+                        {
+                            self.skip_bracket_pair("{", "}")?;
+                        }
+                        else
+                        {
+                            return Ok(());
+                        }
+                    },
+                    "<" => self.skip_template_brackets()?,
+                    _ => self.skip_token(), // `,`, `::` and `...`
+                },
+                _ => self.skip_token(),
+            }
+        }
+
+        Err("expected the constructor body, found end of file".into())
+    }
+
+    /// Read the name of an operator after the `operator` keyword, e.g. `==`, `()`, `new[]` or `bool`
+    ///
+    /// Stop at the `(` of the parameter list
+    fn read_operator_name(&mut self) -> Result<String, String>
+    {
+        let mut name_tokens = Vec::<Token>::new();
+
+        // operator() ( ... )
+        if self.is_operator("(")
+        {
+            self.skip_operator("(")?;
+            self.skip_operator(")")?;
+            return Ok("()".into());
+        }
+
+        while !self.eof() && !self.is_operator("(")
+        {
+            if self.is_operator("<") && matches!(name_tokens.last(), Some(Token::Identifier(_)))
+            {
+                // Template arguments of a conversion function, e.g. `operator std::vector<int>`
+                let start = self.pos;
+                self.skip_template_brackets()?;
+                name_tokens.extend_from_slice(&self.tokens[start..self.pos]);
+                continue;
+            }
+            name_tokens.push(self.token().clone());
+            self.skip_token();
+        }
+
+        Ok(join_tokens(&name_tokens))
+    }
+
+    fn get_declaration(&mut self) -> Result<Option<String>, String>
+    {
+        // Possibly qualified name, e.g. `x` or `Foo::bar`
+        let mut name = String::new();
+        let mut after_scope_operator = false;
+        let intermediate_operators = ["*", "&"];
+        let final_operators = [";", "(", "=", "{", ","];
+        self.name_start = self.pos;
+        self.name_end = self.pos;
+        self.nested_declarator_start = None;
+        self.nested_function_parameters = None;
+        self.template_arguments = None;
+
+        'outer: while !self.eof()
+        {
+            if self.is_attribute_start()
+            {
+                self.read_attribute()?;
+                continue;
+            }
+
+            if is_asm_label_start(&self.tokens[self.pos..])
+            {
+                // int x __asm__("real_name");
+                self.skip_token();
+                self.skip_bracket_pair("(", ")")?;
+                continue;
+            }
+
+            if let Token::Identifier(s) = self.token().clone()
+            {
+                if !after_scope_operator && !matches!(s.as_str(), "virtual" | "static" | "inline"
+                    | "constexpr" | "consteval" | "const" | "volatile")
+                {
+                    self.name_start = self.pos;
+                }
+
+                match s.as_str()
+                {
+                    "const" | "volatile" => self.skip_token(),
+                    "operator" => {
+                        // We need to check if `operator` is a variable name in C or an operator in C++
+                        self.skip_identifier("operator")?;
+                        self.template_arguments = None;
+                        self.assert_not_eof("expected an operator after `operator`, found end of file")?;
+
+                        match self.token().clone()
+                        {
+                            Token::Operator(s) if s == ";" => {
+                                // It is a declaration of variable `operator`
+                                name = append_name_component(&name, "operator", after_scope_operator);
+                            },
+                            Token::Operator(s) if s == "(" => {
+                                if self.next_token() == Some(&Token::Operator(")".into()))
+                                    && self.tokens.get(self.pos + 2) == Some(&Token::Operator("(".into()))
+                                {
+                                    // It is a function call operator `operator()( ... )`
+                                    name = append_name_component(&name, &format!("operator{}", self.read_operator_name()?), after_scope_operator);
+                                }
+                                else
+                                {
+                                    // It is a declaration of function `operator( ... )`
+                                    name = append_name_component(&name, "operator", after_scope_operator);
+                                }
+                            },
+                            Token::Operator(s) if s == "=" => {
+                                match self.next_token()
+                                {
+                                    Some(Token::Operator(s)) if s == "=" || s == "(" => {
+                                        // It is `operator ==` or `operator =`
+                                        name = append_name_component(&name, &format!("operator{}", self.read_operator_name()?), after_scope_operator);
+                                    },
+                                    _ => {
+                                        // It is assignemnt to variable `operator`
+                                        name = append_name_component(&name, "operator", after_scope_operator);
+                                    },
+                                }
+                            },
+                            _ => {
+                                // It is an operator overload or a conversion function in C++
+                                let operator_name = self.read_operator_name()?;
+                                let separator = if operator_name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_') { " " } else { "" };
+                                name = append_name_component(&name, &format!("operator{}{}", separator, operator_name), after_scope_operator);
+                            },
+                        }
+                        after_scope_operator = false;
+                        self.name_end = self.pos;
+                        continue;
+                    },
+                    "virtual" => {
+                        self.specifiers.push(Specifier::Virtual);
+                        self.skip_token();
+                    },
+                    "static" => {
+                        self.specifiers.push(Specifier::Static);
+                        self.skip_token();
+                    },
+                    "inline" => {
+                        self.specifiers.push(Specifier::Inline);
+                        self.skip_token();
+                    },
+                    "constexpr" => {
+                        self.specifiers.push(Specifier::Constexpr);
+                        self.skip_token();
+                    },
+                    "consteval" => {
+                        self.specifiers.push(Specifier::Consteval);
+                        self.skip_token();
+                    },
+                    "thread_local" | "_Thread_local" | "__thread" => {
+                        self.specifiers.push(Specifier::ThreadLocal);
+                        self.skip_token();
+                    },
+                    _ if is_typeof_specifier(self.token()) && self.next_token() == Some(&Token::Operator("(".into())) => {
+                        // Type specifier, e.g. `decltype(x) y;` or `decltype(auto) f();`
+                        self.skip_token();
+                        self.skip_bracket_pair("(", ")")?;
+                    },
+                    _ => {
+                        name = append_name_component(&name, &s, after_scope_operator);
+                        self.template_arguments = None;
+                        self.skip_token();
+                        self.name_end = self.pos;
+                    }
                 }
-                else if s == "{"
+                after_scope_operator = false;
+                continue;
+            }
+
+            for op in intermediate_operators
+            {
+                if *self.token() == Token::Operator(op.into())
                 {
+                    // `*` after `::` is a pointer to member, e.g. `int Foo::*pm;`
+                    after_scope_operator = false;
                     self.skip_token();
-                    self.skip_to_operator_inclusive("}");
+                    continue 'outer;
                 }
-                else if s == "["
+            }
+
+            if *self.token() == Token::Operator("(".into()) && self.is_nested_declarator_start()
+            {
+                // void (*f)(int);
+                self.nested_declarator_start = Some(self.pos);
+                let nested_name = self.read_nested_declarator()?;
+                self.skip_declarator_suffixes()?;
+                match self.token()
                 {
-                    self.skip_token();
-                    self.skip_to_operator_inclusive("]");
+                    Token::Operator(s) if final_operators.contains(&s.as_str()) => return Ok(nested_name),
+                    token => return Err(format!("unexpected {} after a nested declarator", describe_token(token))),
                 }
-                else
+            }
+
+            if *self.token() == Token::Operator(":".into())
+            {
+                if self.next_token() == Some(&Token::Operator(":".into()))
+                {
+                    // `::` is tokenized as two `:`
+                    self.skip_operator(":")?;
+                    self.skip_operator(":")?;
+                    after_scope_operator = true;
+                    self.template_arguments = None;
+                    continue;
+                }
+
+                // Bit field, e.g. `int x : 3;` or unnamed `unsigned : 3;`
+                if name.is_empty() || is_type_keyword(&name)
+                {
+                    return Ok(None);
+                }
+                return Ok(Some(name));
+            }
+
+            if *self.token() == Token::Operator("~".into())
+            {
+                // Destructor
+                if !after_scope_operator
+                {
+                    self.name_start = self.pos;
+                }
+                self.skip_operator("~")?;
+                self.template_arguments = None;
+                if let Token::Identifier(s) = self.token()
                 {
+                    name = append_name_component(&name, &format!("~{}", s), after_scope_operator);
+                    after_scope_operator = false;
                     self.skip_token();
+                    self.name_end = self.pos;
+                    continue;
                 }
+                return Err(format!("expected an identifier after `~`, found {}", describe_token(self.token())));
             }
-            else
+
+            if *self.token() == Token::Operator("<".into())
             {
-                self.skip_token();
+                // Template arguments of a specialisation are not a part of its type, e.g. `f<int>`
+                let follows_name = self.pos == self.name_end;
+                self.template_arguments = Some(self.read_template_arguments()?);
+                if follows_name
+                {
+                    self.name_end = self.pos;
+                }
+                continue;
+            }
+
+            if *self.token() == Token::Operator("[".into()) && !name.is_empty()
+            {
+                // Array, e.g. `int a[10];`
+                self.skip_bracket_pair("[", "]")?;
+                continue;
+            }
+
+            for op in final_operators
+            {
+                if *self.token() == Token::Operator(op.into())
+                {
+                    if name.is_empty()
+                    {
+                        return Ok(None);
+                    }
+                    return Ok(Some(name));
+                }
             }
+
+            return Err(format!("unexpected {} in a declaration", describe_token(self.token())));
         }
 
-        panic!("skip_to_one_of_operators: EOF");
+        Err("unexpected end of file in a declaration".into())
     }
 
-    fn skip_to_operator_inclusive(&mut self, operator: &str)
+    /// Check if `(` at the pointer opens a nested declarator, e.g. `(*f)`, `(&r)` or `(Foo::*pm)`,
+    /// and not a parameter list
+    fn is_nested_declarator_start(&self) -> bool
     {
-        while !self.eof()
+        let mut i = self.pos + 1;
+        loop
         {
-            if let Token::Operator(s) = &self.token()
+            match self.tokens.get(i)
             {
-                if s == operator
-                {
+                Some(Token::Operator(s)) => return s == "*" || s == "&" || s == "^",
+                // Qualifier of a pointer to member, e.g. `Foo::` in `(Foo::*pm)`
+                Some(Token::Identifier(_)) if self.tokens.get(i + 1) == Some(&Token::Operator(":".into()))
+                    && self.tokens.get(i + 2) == Some(&Token::Operator(":".into())) => i += 3,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Read a declarator in parentheses, e.g. `(*f)`, `(*table[8])` or `(*make(int))`
+    ///
+    /// Returns the declared name, `None` for abstract declarators, e.g. `(*)`.
+    /// The pointer is at `(`.
+    fn read_nested_declarator(&mut self) -> Result<Option<String>, String>
+    {
+        self.skip_operator("(")?;
+
+        let mut name = None;
+        loop
+        {
+            self.assert_not_eof("expected `)`, found end of file")?;
+            match self.token().clone()
+            {
+                Token::Operator(s) if s == ")" => break,
+                Token::Operator(s) if matches!(s.as_str(), "*" | "&" | "^") => self.skip_token(),
+                Token::Operator(s) if s == "(" && name.is_none() => {
+                    name = self.read_nested_declarator()?;
+                },
+                Token::Operator(s) if s == "(" => {
+                    // Parameters of a function declared in a nested declarator, e.g. `(int)` in `(*make(int))`
+                    let start = self.pos;
+                    self.skip_bracket_pair("(", ")")?;
+                    if self.nested_function_parameters.is_none() && start == self.name_end
+                    {
+                        self.nested_function_parameters = Some((start, self.pos));
+                    }
+                },
+                Token::Operator(s) if s == "[" => self.skip_bracket_pair("[", "]")?,
+                _ if self.is_attribute_start() => self.read_attribute()?,
+                Token::Identifier(s) if matches!(s.as_str(), "const" | "volatile" | "restrict" | "__restrict" | "__restrict__") => self.skip_token(),
+                Token::Identifier(_) if name.is_none() => {
+                    // Possibly qualified name, or a qualifier of a pointer to member, e.g. `Foo::*`
+                    let start = self.pos;
+                    let qualified_name = self.read_qualified_name()?;
+                    if self.tokens[self.pos - 1] == Token::Operator(":".into())
+                    {
+                        // `::` is followed by `*`
+                        continue;
+                    }
+                    self.name_start = start;
+                    self.name_end = self.pos;
+                    name = Some(qualified_name);
+                },
+                token => return Err(format!("unexpected {} in a nested declarator", describe_token(&token))),
+            }
+        }
+
+        self.skip_operator(")")?;
+        Ok(name)
+    }
+
+    /// Skip parameter lists, arrays and qualifiers following a nested declarator,
+    /// e.g. `(int) const noexcept` in `void (Foo::*f)(int) const noexcept;` or `[10]` in `int (&r)[10];`
+    fn skip_declarator_suffixes(&mut self) -> Result<(), String>
+    {
+        loop
+        {
+            match self.token()
+            {
+                Token::Operator(s) if s == "(" => self.skip_bracket_pair("(", ")")?,
+                Token::Operator(s) if s == "[" && !self.is_attribute_start() => self.skip_bracket_pair("[", "]")?,
+                Token::Identifier(s) if matches!(s.as_str(), "const" | "volatile") => self.skip_token(),
+                Token::Identifier(s) if s == "noexcept" || s == "throw" => {
                     self.skip_token();
-                    return
-                }
-                else if s == "("
+                    if self.is_operator("(")
+                    {
+                        self.skip_bracket_pair("(", ")")?;
+                    }
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn add_variable_or_function(&mut self, name: &str, kind: DeclarationKind, is_definition: bool) -> &mut Declaration
+    {
+        // Out-of-class definition of a member, e.g. `void Foo::bar() {}`
+        let (qualifier, _) = split_qualified_name(name);
+        let class_name = if qualifier.is_empty() { None } else { Some(self.qualify(qualifier)) };
+
+        let declaration = self.add_declaration(name, kind);
+        declaration.is_definition = is_definition;
+        declaration.class_name = class_name;
+        declaration
+    }
+
+    /// Process a declaration of variables or a function
+    ///
+    /// int a;
+    /// int b = 1, *c, d[2] = {1, 2};
+    /// int e : 3;
+    /// int f();
+    /// int g() { ... }
+    fn process_variables_or_function(&mut self) -> Result<(), String>
+    {
+        self.process_declarators(None)
+    }
+
+    /// Type of a declarator read by get_declaration from `start` up to the pointer, without the name,
+    /// e.g. `char*`, `int[10]` or `void (*)(int)`
+    ///
+    /// `base_type` is the type shared by all declarators, e.g. `char` in `char *a, b[2];`.
+    /// It is taken from the first declarator if not known yet.
+    /// Parameters of a function declared in a nested declarator are not a part of its type.
+    fn get_declarator_type(&self, start: usize, base_type: &mut Option<Vec<Token>>) -> String
+    {
+        let nested_start = self.nested_declarator_start.filter(|nested_start| *nested_start < self.name_start);
+        let prefix = strip_declaration_specifiers(&self.tokens[start..nested_start.unwrap_or(self.name_start)]);
+        let prefix = match base_type
+        {
+            Some(base_type) => [base_type.clone(), prefix].concat(),
+            None => {
+                *base_type = Some(split_declarator_type(&prefix).0);
+                prefix
+            },
+        };
+
+        let mut suffix = self.tokens[nested_start.unwrap_or(self.name_start)..self.name_start].to_vec();
+        for i in self.name_end..self.pos
+        {
+            if !matches!(self.nested_function_parameters, Some((begin, end)) if (begin..end).contains(&i))
+            {
+                suffix.push(self.tokens[i].clone());
+            }
+        }
+        let suffix = strip_declaration_specifiers(&suffix);
+
+        match nested_start
+        {
+            Some(_) => format!("{} {}", join_tokens(&prefix), join_tokens(&suffix)),
+            None => join_tokens(&[prefix, suffix].concat()),
+        }
+    }
+
+    /// Process declarators of variables or a function, see process_variables_or_function
+    fn process_declarators(&mut self, mut base_type: Option<Vec<Token>>) -> Result<(), String>
+    {
+        loop
+        {
+            let start = self.pos;
+            let declaration = self.get_declaration()?;
+            let type_name = self.get_declarator_type(start, &mut base_type);
+            let template_arguments = self.template_arguments.take();
+            let specialization = self.take_specialization(&template_arguments);
+
+            if let Some((parameters_start, _)) = self.nested_function_parameters
+                && let Some(declaration) = &declaration
+            {
+                // Function declared in a nested declarator, e.g. `int (*make(int))(double);`
+                let mut signature = FunctionSignature {
+                    template_parameters: self.template_parameters.take(),
+                    requires_clause: self.requires_clause.take(),
+                    return_type: type_name,
+                    ..Default::default()
+                };
+                let end = self.pos;
+                self.pos = parameters_start;
+                self.read_function_parameters(&mut signature)?;
+                self.pos = end;
+
+                let is_definition = self.is_operator("{");
+                if is_definition
                 {
-                    self.skip_token();
-                    self.skip_to_operator_inclusive(")");
+                    self.skip_bracket_pair("{", "}")?;
                 }
-                else if s == "{"
+                else
                 {
-                    self.skip_token();
-                    self.skip_to_operator_inclusive("}");
+                    self.skip_operator(";")?;
                 }
-                else if s == "["
+                let declaration = self.add_variable_or_function(declaration, DeclarationKind::Function, is_definition);
+                declaration.signature = Some(signature);
+                declaration.specialization = specialization;
+                declaration.template_arguments = template_arguments;
+                return Ok(());
+            }
+
+            // get_declaration stops at one of final operators
+            let operator = match self.token()
+            {
+                Token::Operator(s) => s.clone(),
+                token => return Err(format!("unexpected {}", describe_token(token))),
+            };
+
+            if operator == "("
+            {
+                let mut signature = FunctionSignature {
+                    template_parameters: self.template_parameters.take(),
+                    requires_clause: self.requires_clause.take(),
+                    return_type: type_name,
+                    ..Default::default()
+                };
+                let is_definition = self.read_function_signature(&mut signature)?;
+                if let Some(declaration) = declaration
                 {
-                    self.skip_token();
-                    self.skip_to_operator_inclusive("]");
+                    let declaration = self.add_variable_or_function(&declaration, DeclarationKind::Function, is_definition);
+                    declaration.signature = Some(signature);
+                    declaration.specialization = specialization;
+                    declaration.template_arguments = template_arguments;
                 }
-                else
-                {
+                return Ok(());
+            }
+
+            let is_lambda = match operator.as_str()
+            {
+                ";" | "," => false,
+                "{" => {
+                    self.skip_bracket_pair("{", "}")?;
+                    false
+                },
+                "=" | ":" => {
                     self.skip_token();
+                    self.skip_initializer()?
+                },
+                _ => return Err(format!("unexpected `{}` after a declarator", operator)),
+            };
+
+            if let Some(declaration) = declaration
+            {
+                // Static data members are defined outside of the class
+                let is_declaration_only = self.specifiers.contains(&Specifier::Extern)
+                    || (self.is_in_class() && self.specifiers.contains(&Specifier::Static));
+                // Non-template `const` variables at namespace scope have internal linkage in C++
+                let is_internal_constant = self.dialect == Dialect::Cpp
+                    && !self.is_in_class()
+                    && self.template_parameters.is_none()
+                    && (is_const_type(&type_name) || self.specifiers.contains(&Specifier::Constexpr))
+                    && !self.specifiers.iter().any(|s| matches!(s, Specifier::Extern | Specifier::Inline));
+                let has_initializer = operator == "=" || operator == "{";
+                let declaration = self.add_variable_or_function(&declaration, DeclarationKind::Variable, has_initializer || !is_declaration_only);
+                if is_internal_constant && declaration.class_name.is_none() && declaration.linkage == Linkage::External
+                {
+                    declaration.linkage = Linkage::Internal;
+                    declaration.language_linkage = None;
                 }
+                declaration.type_name = Some(type_name);
+                declaration.is_lambda = is_lambda;
+                declaration.specialization = specialization;
+                declaration.template_arguments = template_arguments;
+            }
+
+            // After an initializer or a bit field width
+            if self.is_operator(",")
+            {
+                self.skip_operator(",")?;
             }
             else
             {
-                self.skip_token();
+                self.skip_operator(";")?;
+                return Ok(());
+            }
+        }
+    }
+
+    /// Read tokens up to a `,` or `;` outside of brackets, leaving the pointer at the separator
+    fn read_to_separator(&mut self) -> Result<Vec<Token>, String>
+    {
+        let start = self.pos;
+        let mut depth = 0;
+        loop
+        {
+            match self.token()
+            {
+                Token::Operator(s) if depth == 0 && (s == "," || s == ";") => break,
+                Token::Operator(s) if matches!(s.as_str(), "<" | "(" | "[" | "{") => depth += 1,
+                Token::Operator(s) if matches!(s.as_str(), ">" | ")" | "]" | "}") => depth -= 1,
+                _ => self.assert_not_eof("expected `,` or `;`, found end of file")?,
+            }
+            self.skip_token();
+        }
+        Ok(self.tokens[start..self.pos].to_vec())
+    }
+
+    /// Read a requires-clause, e.g. `requires std::integral<T> && (sizeof(T) > 1)`
+    ///
+    /// Returns the constraints as written, without `requires`.
+    fn read_requires_clause(&mut self) -> Result<String, String>
+    {
+        self.skip_identifier("requires")?;
+        let start = self.pos;
+        loop
+        {
+            match self.token().clone()
+            {
+                Token::Operator(s) if s == "(" => self.skip_bracket_pair("(", ")")?,
+                // Requires-expression, e.g. `requires (T a) { a + a; }`
+                Token::Identifier(s) if s == "requires" => {
+                    self.skip_token();
+                    if self.is_operator("(")
+                    {
+                        self.skip_bracket_pair("(", ")")?;
+                    }
+                    self.skip_bracket_pair("{", "}")?;
+                },
+                // Possibly qualified concept or constant, e.g. `std::integral<T>` or `true`
+                Token::Identifier(_) => {
+                    self.skip_token();
+                    if self.is_operator("<")
+                    {
+                        self.skip_template_brackets()?;
+                    }
+                    if self.is_operator(":") && self.next_token() == Some(&Token::Operator(":".into()))
+                    {
+                        self.pos += 2;
+                        continue;
+                    }
+                },
+                Token::Operator(s) if s == ":" && self.next_token() == Some(&Token::Operator(":".into())) => {
+                    self.pos += 2;
+                    continue;
+                },
+                token => return Err(format!("expected a constraint, found {}", describe_token(&token))),
+            }
+
+            // Conjunction and disjunction
+            match (self.token(), self.next_token())
+            {
+                (Token::Operator(a), Some(Token::Operator(b))) if a == b && (a == "&" || a == "|") => self.pos += 2,
+                _ => return Ok(self.source_text(start, self.pos)),
+            }
+        }
+    }
+
+    /// Process a concept, e.g. `template <typename T> concept Integral = std::is_integral_v<T>;`
+    fn process_concept(&mut self) -> Result<(), String>
+    {
+        self.skip_identifier("concept")?;
+        let name = match self.token()
+        {
+            Token::Identifier(s) => s.clone(),
+            token => return Err(format!("expected a concept name, found {}", describe_token(token))),
+        };
+        self.skip_token();
+        while self.is_attribute_start()
+        {
+            self.read_attribute()?;
+        }
+        self.skip_operator("=")?;
+
+        let start = self.pos;
+        self.skip_to_operator_inclusive(";")?;
+        let constraint = self.source_text(start, self.pos - 1);
+        let template_parameters = self.template_parameters.take();
+        let declaration = self.add_declaration(&name, DeclarationKind::Concept);
+        declaration.initializer = Some(constraint);
+        declaration.template_parameters = template_parameters;
+        declaration.is_definition = true;
+        Ok(())
+    }
+
+    /// Process all forms of `using`:
+    ///
+    /// using T = int;
+    /// template <typename T> using Vec = std::vector<T>;
+    /// using std::swap, ::size_t;
+    /// using enum Color;
+    /// using namespace std;
+    fn process_using(&mut self) -> Result<(), String>
+    {
+        self.skip_identifier("using")?;
+        self.assert_not_eof("unexpected end of file after `using`")?;
+
+        // using namespace A::B;
+        // using enum Color;
+        let kind = match self.token()
+        {
+            Token::Identifier(s) if s == "namespace" => Some(DeclarationKind::UsingDirective),
+            Token::Identifier(s) if s == "enum" => Some(DeclarationKind::UsingEnum),
+            _ => None,
+        };
+        if let Some(kind) = kind
+        {
+            self.skip_token();
+            let target = join_tokens(&self.read_to_separator()?);
+            let target = target.trim_start_matches("::");
+            self.add_declaration(split_qualified_name(target).1, kind).target = Some(target.into());
+            return self.skip_operator(";");
+        }
+
+        // using T [[deprecated]] = int;
+        if let Token::Identifier(name) = self.token().clone()
+            && let Some(Token::Operator(s)) = self.next_token()
+            && (s == "=" || s == "[" || is_attribute_start(&self.tokens[self.pos + 1..]))
+        {
+            self.skip_token();
+            while self.is_attribute_start()
+            {
+                self.read_attribute()?;
+            }
+            self.skip_operator("=")?;
+            let target = join_tokens(&self.read_to_separator()?);
+            let template_parameters = self.template_parameters.take();
+            let declaration = self.add_declaration(&name, DeclarationKind::TypeAlias);
+            declaration.target = Some(target);
+            declaration.template_parameters = template_parameters;
+            return self.skip_operator(";");
+        }
+
+        // using A::b, typename B<T>::c, Ts::operator()...;
+        loop
+        {
+            if self.is_identifier("typename")
+            {
+                self.skip_token();
+            }
+            let mut tokens = self.read_to_separator()?;
+            // Pack expansion
+            while tokens.last() == Some(&Token::Operator(".".into()))
+            {
+                tokens.pop();
+            }
+            let target = join_tokens(&tokens);
+            let target = target.trim_start_matches("::");
+            if target.is_empty()
+            {
+                return Err(format!("expected a name after `using`, found {}", describe_token(self.token())));
+            }
+            self.add_declaration(split_qualified_name(target).1, DeclarationKind::UsingDeclaration).target = Some(target.into());
+
+            if self.is_operator(",")
+            {
+                self.skip_operator(",")?;
+                continue;
+            }
+            return self.skip_operator(";");
+        }
+    }
+
+    /// Process all forms of enums:
+    ///
+    /// enum A { a1, a2 = 1 << 3 };
+    /// enum class B : std::uint8_t { b1, b2 };
+    /// enum C : int;
+    /// enum { d1 };
+    /// enum A a;
+    fn process_enum(&mut self) -> Result<(), String>
+    {
+        match self.process_enum_without_declarators()?
+        {
+            Some(type_name) => self.process_declarators_of_type(type_name),
+            None => self.process_variables_or_function(),
+        }
+    }
+
+    /// Process an enum up to the end of its body, without declarators following it
+    ///
+    /// Returns the qualified name of the enum (`<unnamed enum>` for anonymous ones),
+    /// or `None` for an elaborated type specifier, e.g. `enum A a;`, leaving the pointer at `enum`.
+    fn process_enum_without_declarators(&mut self) -> Result<Option<String>, String>
+    {
+        let start = self.pos;
+        self.skip_identifier("enum")?;
+        self.assert_not_eof("unexpected end of file after `enum`")?;
+
+        // Enumerators of scoped enums are not visible in the enclosing scope
+        let is_scoped = self.is_identifier("struct") || self.is_identifier("class");
+        if is_scoped
+        {
+            self.skip_token();
+        }
+        while self.is_attribute_start()
+        {
+            self.read_attribute()?;
+        }
+
+        // Possibly qualified name, e.g. `E` or `Foo::E`
+        let name = self.read_qualified_name()?;
+        if is_scoped && name.is_empty()
+        {
+            return Err(format!("expected an identifier after `enum class`, found {}", describe_token(self.token())));
+        }
+
+        let mut underlying_type = None;
+        if self.is_operator(":")
+        {
+            self.skip_operator(":")?;
+            let start = self.pos;
+            self.skip_to_one_of_operators(vec![";", "{"])?;
+            underlying_type = Some(join_tokens(&self.tokens[start..self.pos]));
+        }
+
+        if !self.is_operator("{") && !self.is_operator(";")
+        {
+            // Elaborated type specifier, e.g. `enum A a;`
+            if name.is_empty()
+            {
+                return Err(format!("unexpected {} after `enum`", describe_token(self.token())));
+            }
+            self.pos = start;
+            return Ok(None);
+        }
+
+        let type_name = if name.is_empty() { "<unnamed enum>".into() } else { self.qualify(&name) };
+        let is_definition = self.is_operator("{");
+        if !name.is_empty()
+            && let Some(declaration) = self.add_unique_declaration(&name, DeclarationKind::Enum)
+        {
+            declaration.is_definition = is_definition;
+            declaration.underlying_type = underlying_type;
+        }
+        if !is_definition
+        {
+            return Ok(Some(type_name));
+        }
+
+        self.skip_operator("{")?;
+        loop
+        {
+            match self.token().clone()
+            {
+                Token::Operator(s) if s == "}" => {
+                    self.skip_operator("}")?;
+                    return Ok(Some(type_name));
+                },
+                Token::Operator(s) if s == "," => self.skip_operator(",")?,
+                Token::Identifier(s) => {
+                    // Enumerators have their own attributes, e.g. `A [[deprecated]] = 1`
+                    self.skip_token();
+                    self.attributes.clear();
+                    while self.is_attribute_start()
+                    {
+                        self.read_attribute()?;
+                    }
+
+                    let mut initializer = None;
+                    if self.is_operator("=")
+                    {
+                        self.skip_operator("=")?;
+                        let start = self.pos;
+                        self.skip_to_one_of_operators(vec![",", "}"])?;
+                        initializer = Some(self.source_text(start, self.pos));
+                    }
+
+                    let enumerator = if is_scoped { format!("{}::{}", name, s) } else { s };
+                    if let Some(declaration) = self.add_unique_declaration(&enumerator, DeclarationKind::Enumerator)
+                    {
+                        declaration.initializer = initializer;
+                    }
+                },
+                token => return Err(format!("unexpected {} in an enum", describe_token(&token))),
             }
         }
+    }
 
-        panic!("skip_to_operator_inclusive: EOF while looking for operator {}", operator);
+    /// Read template arguments in `<` and `>`, e.g. `std::pair<int, int>` for `<std::pair<int, int>>`
+    fn read_template_arguments(&mut self) -> Result<String, String>
+    {
+        let start = self.pos;
+        self.skip_template_brackets()?;
+        Ok(join_tokens(&self.tokens[start + 1..self.pos - 1]))
     }
 
-    fn skip_bracket_pair(&mut self, opening_bracket: &str, closing_bracket: &str)
+    /// Specialisation kind of the current declaration, given the template arguments after its name
+    fn take_specialization(&mut self, template_arguments: &Option<String>) -> Option<Specialization>
     {
-        if self.eof()
+        match self.specialization.take()
         {
-            panic!("skip_bracket_pair: {} expected, EOF found", opening_bracket);
+            Some(specialization) => Some(specialization),
+            None if template_arguments.is_some() && self.template_parameters.is_some() => Some(Specialization::Partial),
+            None => None,
         }
+    }
 
-        self.skip_token();
-
-        while !self.eof()
+    /// Read a possibly qualified name, e.g. `S`, `ns::S` or `S<int>`
+    ///
+    /// Template arguments are not a part of the name, arguments of the last component are kept in `template_arguments`.
+    /// The name is empty if there is no identifier.
+    fn read_qualified_name(&mut self) -> Result<String, String>
+    {
+        let mut name = String::new();
+        while let Token::Identifier(s) = self.token().clone()
         {
-            let token = self.token();
-
-            if *token == Token::Operator(closing_bracket.into())
-            {
-                self.skip_token();
-                return
-            }
-            else if *token == Token::Operator("(".into())
-            {
-                self.skip_bracket_pair("(", ")");
-            }
-            else if *token == Token::Operator("{".into())
-            {
-                self.skip_bracket_pair("{", "}");
-            }
-            else if *token == Token::Operator("[".into())
+            name = append_name_component(&name, &s, true);
+            self.skip_token();
+            self.template_arguments = None;
+            if self.is_operator("<")
             {
-                self.skip_bracket_pair("[", "]");
+                self.template_arguments = Some(self.read_template_arguments()?);
             }
-            else
+            if !self.is_operator(":") || self.next_token() != Some(&Token::Operator(":".into()))
             {
-                self.skip_token();
+                break;
             }
+            self.skip_operator(":")?;
+            self.skip_operator(":")?;
         }
+        Ok(name)
+    }
 
-        panic!("skip_bracket_pair: EOF");
+    /// Check if the token at `pos` ends the name of a class, i.e. starts its body or its base classes
+    fn is_class_head_end(&self, pos: usize) -> bool
+    {
+        matches!(self.tokens.get(pos), Some(Token::Operator(s)) if s == "{" || s == ":")
     }
 
-    fn skip_function(&mut self)
+    /// Skip `final` after the name of a class, e.g. `class A final : public B`
+    fn skip_final_specifier(&mut self)
     {
-        // To handle function declarations and definitions, e.g.:
-        //
-        // int myFunc ( ... );
-        // int myFunc ( ... ) { ... }
-        //
-        // The pointer is at the "("
+        if self.token() == &Token::Identifier("final".into()) && self.is_class_head_end(self.pos + 1)
+        {
+            self.skip_token();
+        }
+    }
 
-        // 1. Check that we point at "("
+    /// Process a class, struct or union up to the end of its body, without declarators following it
+    ///
+    /// Returns the qualified name of the type (`<unnamed struct>` for anonymous ones),
+    /// or `None` for an elaborated type specifier, e.g. `struct S *p;`, leaving the pointer at the class-key.
+    fn process_class_or_struct_without_semicolon(&mut self) -> Result<Option<String>, String>
+    {
+        let start = self.pos;
+        let (kind, default_access, keyword) = match self.token()
+        {
+            Token::Identifier(s) if s == "class" => (DeclarationKind::Class, Access::Private, "class"),
+            Token::Identifier(s) if s == "union" => (DeclarationKind::Union, Access::Public, "union"),
+            _ => (DeclarationKind::Struct, Access::Public, "struct"),
+        };
+        self.skip_token(); // skip `class`/`struct`/`union` keyword
+
+        // struct [[gnu::packed]] alignas(8) S
+        while self.is_attribute_start()
+        {
+            self.read_attribute()?;
+        }
 
         if self.eof()
         {
-            panic!("skip_function: '(' expected, EOF found");
+            return Err("expected a class name, found end of file".into());
         }
 
-        if *self.token() != Token::Operator("(".into())
+        let mut name = self.read_qualified_name()?;
+        self.skip_final_specifier();
+
+        // The first identifier is a macro in `class EXPORT_MACRO Name { ... }`
+        if !name.is_empty()
+            && matches!(self.token(), Token::Identifier(_))
+            && (self.is_class_head_end(self.pos + 1) || (self.tokens.get(self.pos + 1) == Some(&Token::Identifier("final".into())) && self.is_class_head_end(self.pos + 2)))
         {
-            panic!("skip_function: '(' expected, {:?} found", self.token());
+            name = self.read_qualified_name()?;
+            self.skip_final_specifier();
         }
 
-        // 2. Skip parameter list (round brackets)
+        match self.token()
+        {
+            Token::Operator(s) if s == "{" || s == ";" => {},
+            // Base classes
+            Token::Operator(s) if s == ":" => {
+                while !self.is_operator(";") && !self.is_operator("{")
+                {
+                    if self.eof()
+                    {
+                        return Err("expected `;` or `{`, found end of file".into());
+                    }
+                    self.skip_token();
+                }
+            },
+            // Elaborated type specifier, e.g. `struct S *p;` or `struct S f();`
+            _ if !name.is_empty() => {
+                self.pos = start;
+                return Ok(None);
+            },
+            token => return Err(format!("unexpected {} after `{}`", describe_token(token), keyword)),
+        }
 
-        self.skip_bracket_pair("(", ")");
+        if name.is_empty()
+        {
+            // Anonymous class, struct or union
+            if self.is_operator("{")
+            {
+                self.skip_bracket_pair("{", "}")?;
+            }
+            return Ok(Some(format!("<unnamed {}>", keyword)));
+        }
 
-        // 3. Skip __attribute__(( ... )) if needed
+        let template_arguments = self.template_arguments.take();
+        let specialization = self.take_specialization(&template_arguments);
 
-        if self.eof()
+        if self.is_operator(";")
         {
-            panic!("skip_function: ';', '{{' or '__attribute__' expected, EOF found");
+            // Forward declaration or explicit instantiation
+            let declaration = self.add_declaration(&name, kind);
+            declaration.specialization = specialization;
+            declaration.template_arguments = template_arguments;
+            return Ok(Some(self.qualify(&name)));
         }
 
-        if *self.token() == Token::Identifier("__attribute__".into())
+        // Members are collected into a separate list
+        let outer_declarations = std::mem::take(&mut self.declarations);
+        // There are no access specifiers in C
+        let access = if self.dialect == Dialect::Cpp { Some(default_access) } else { None };
+        let outer_access = std::mem::replace(&mut self.access, access);
+        let outer_specifiers = std::mem::take(&mut self.specifiers);
+        let outer_attributes = std::mem::take(&mut self.attributes);
+        // Template parameters of a class template do not apply to its members
+        let template_parameters = self.template_parameters.take();
+        let requires_clause = self.requires_clause.take();
+        self.scopes.push(Scope::Class { name: name.clone() });
+
+        // The outer state is restored even if the class body can't be parsed
+        let result = self.process_class_body();
+
+        self.scopes.pop();
+        self.specifiers = outer_specifiers;
+        self.attributes = outer_attributes;
+        self.access = outer_access;
+        let members = std::mem::replace(&mut self.declarations, outer_declarations);
+        result?;
+
+        // GNU attributes of a type may follow its body, e.g. `struct S { ... } __attribute__((packed));`
+        while self.is_attribute_start()
         {
-            self.skip_attribute();
+            self.read_attribute()?;
         }
 
-        // 4. Check if the next token if ';' or '{'
+        let declaration = self.add_declaration(&name, kind);
+        declaration.members = members;
+        declaration.is_definition = true;
+        declaration.template_parameters = template_parameters;
+        declaration.requires_clause = requires_clause;
+        declaration.specialization = specialization;
+        declaration.template_arguments = template_arguments;
+        Ok(Some(declaration.name.clone()))
+    }
 
-        if self.eof()
-        {
-            panic!("skip_function: ';' or '{{' expected, EOF found");
-        }
+    /// Process declarations inside `{` and `}` of a class
+    fn process_class_body(&mut self) -> Result<(), String>
+    {
+        self.skip_operator("{")?;
 
-        if let Token::Operator(s) = self.token()
+        while !self.eof()
         {
-            match s.as_str()
+            self.specifiers.clear();
+            self.attributes.clear();
+            self.template_parameters = None;
+            self.requires_clause = None;
+            self.exported = false;
+            self.specialization = None;
+
+            match self.token().clone()
             {
-                ";" => {
-                    // 5A. It is a function declaration. Skip ";"
-                    self.skip_token();
-                    return;
+                Token::Operator(s) if s == "}" => {
+                    self.skip_operator("}")?;
+                    return Ok(());
                 },
-                "{" => {
-                    // 5B. It is a function definition. Skip curly brackets
+                Token::Identifier(s) if self.next_token() == Some(&Token::Operator(":".into()))
+                    && matches!(s.as_str(), "public" | "protected" | "private") =>
+                {
+                    self.access = match s.as_str()
+                    {
+                        "public" => Some(Access::Public),
+                        "protected" => Some(Access::Protected),
+                        _ => Some(Access::Private),
+                    };
                     self.skip_token();
-                    self.skip_to_operator_inclusive("}");
-                    return;
-                }
-                _ => panic!("skip_function: ';' or '{{' expected, {:?} found", self.token()),
+                    self.skip_operator(":")?;
+                },
+                _ => self.process_declaration_or_recover(),
             }
         }
-        else
+
+        Err("expected `}`, found end of file".into())
+    }
+
+    /// Process a class, struct or union with declarators following it, e.g. `struct S { ... } a, *b;`
+    fn process_class_or_struct(&mut self) -> Result<(), String>
+    {
+        match self.process_class_or_struct_without_semicolon()?
         {
-            panic!("skip_function: ';' or '{{' expected, {:?} found", self.token());
+            Some(type_name) => self.process_declarators_of_type(type_name),
+            None => self.process_variables_or_function(),
         }
     }
 
-    fn get_declaration(&mut self) -> Option<String>
+    /// Process declarators following a definition of a type, e.g. `a, *b;` after `struct S { ... }`
+    fn process_declarators_of_type(&mut self, type_name: String) -> Result<(), String>
     {
-        let mut last_identifier: String = "".into();
-        let intermediate_operators = ["*", ":", "&"];
-        let final_operators = [";", "(", "="];
-
-        'outer: while !self.eof()
+        if self.is_operator(";")
         {
-            if let Token::Identifier(s) = self.token()
-            {
-                match s.as_str()
-                {
-                    "__attribute__" => self.skip_attribute(),
-                    "operator" => {
-                        // We need to check if `operator` is a variable name in C or an operator in C++
-                        self.skip_identifier("operator");
-                        self.assert_not_eof("EOF after `operator`");
+            return self.skip_operator(";");
+        }
+        self.attributes.clear();
+        self.process_declarators(Some(vec![Token::Identifier(type_name)]))
+    }
 
-                        if let Token::Operator(s) = self.token()
-                        {
-                            match s.as_str()
-                            {
-                                ";" | "(" => {
-                                    // It is a declaration of variable `operator` or function `operator( ... )`
-                                    last_identifier = "operator".into();
-                                    continue;
-                                },
-                                "=" => {
-                                    self.skip_operator("=");
-                                    self.assert_not_eof("EOF after `operator=` or `operator =`");
-                                    if *self.token() == Token::Operator("=".into())
-                                    {
-                                        // It is `operator ==`
-                                        self.skip_operator("=");
-                                        last_identifier = "".into();
-                                        continue;
-                                    }
-                                    else {
-                                        // It is assignemnt to variable `operator`
-                                        last_identifier = "operator".into();
-                                        self.skip_token();
-                                        continue;
-                                    }
-                                },
-                                _ => {
-                                    // It is an operator overload in C++
-                                    self.skip_token();
-                                    if let Token::Operator(s) = self.token()
-                                    {
-                                        if s != "("
-                                        {
-                                            self.skip_token();
-                                        }
-                                    }
-                                    last_identifier = "".into();
-                                    continue;
-                                }
-                            }
-                        }
-                        else {
-                            panic!();
-                        }
-                    },
-                    _ => {
-                        last_identifier = s.clone();
-                        self.skip_token();
-                    }
-                }
-                continue;
-            }
+    /// Process typedefs, including ones defining a type:
+    ///
+    /// typedef int a, *b;
+    /// typedef struct c { ... } d;
+    /// typedef enum { ... } e;
+    /// typedef void f(int);
+    fn process_typedef(&mut self) -> Result<(), String>
+    {
+        self.skip_identifier("typedef")?;
 
-            for op in intermediate_operators
+        let type_name = match self.token()
+        {
+            Token::Identifier(s) if matches!(s.as_str(), "struct" | "union") => self.process_class_or_struct_without_semicolon()?,
+            Token::Identifier(s) if s == "class" && self.dialect == Dialect::Cpp => self.process_class_or_struct_without_semicolon()?,
+            Token::Identifier(s) if s == "enum" => self.process_enum_without_declarators()?,
+            Token::Identifier(_) => None,
+            token => return Err(format!("expected an identifier in a typedef, found {}", describe_token(token))),
+        };
+        let mut base_type = type_name.map(|name| vec![Token::Identifier(name)]);
+
+        loop
+        {
+            let start = self.pos;
+            let declaration = self.get_declaration()?;
+            if self.is_operator("(")
             {
-                if *self.token() == Token::Operator(op.into())
-                {
-                    self.skip_token();
-                    continue 'outer;
-                }
+                // Function type, e.g. `typedef void f(int);`
+                self.skip_declarator_suffixes()?;
             }
-
-            if *self.token() == Token::Operator("<".into())
+            let type_name = self.get_declarator_type(start, &mut base_type);
+            if let Some(declaration) = declaration
             {
-                self.skip_template_brackets();
-                continue;
+                self.add_declaration(&declaration, DeclarationKind::Typedef).type_name = Some(type_name);
             }
 
-            for op in final_operators
+            if self.is_operator(",")
             {
-                if *self.token() == Token::Operator(op.into())
-                {
-                    if last_identifier == ""
-                    {
-                        return None;
-                    }
-                    return Some(last_identifier);
-                }
+                self.skip_operator(",")?;
+                continue;
             }
-
-            panic!("get_declaration: Unexpected token: {:?}", self.token());
+            return self.skip_operator(";");
         }
-
-        panic!("get_declaration: EOF");
     }
 
-    fn process_using(&mut self)
+    fn process_extern(&mut self) -> Result<(), String>
     {
-        self.skip_identifier("using");
-        if let Token::Identifier(s) = self.token()
-        {
-            match s.as_str(){
-                "namespace" => self.skip_to_operator_inclusive(";"),
-                _ => {
-                    self.declarations.push(s.to_string());
-                    self.skip_to_operator_inclusive(";");
-                },
-            }
-        }
-        else if *self.token() == Token::Operator(":".into())
-        {
-            // using ::some_namespace::some_type;
-            self.skip_to_operator_inclusive(";");
-        }
-        else
+        self.skip_identifier("extern")?;
+
+        if self.eof()
         {
-            panic!("find_declarations: Identifier expected after `using` but {:?} found", self.token());
+            return Err("unexpected end of file after `extern`".into());
         }
-    }
-
-    fn process_enum(&mut self)
-    {
-        self.skip_identifier("enum");
-        self.assert_not_eof("process_enum: EOF after `enum` keyword");
 
-        // Process scopped enum
-        if self.is_identifier("struct") || self.is_identifier("class")
-        {
-            return; // processing as a struct/class will suffice. It will skip the body
+        // extern int x;
+        //        ^-------- here
+        //
+        // Parse it as ordinary `int x;`
+        if let Token::Identifier(_) = self.token() {
+            self.specifiers.push(Specifier::Extern);
+            self.process_declaration()?;
+            return Ok(());
         }
 
-        // Process unscopped enum
-        if let Token::Identifier(s) = self.token()
+        // extern "C" .....
+        //        ^------- here
+        if let Token::String(s) = self.token()
         {
-            self.add_declaration(s.into());
-            self.skip_token();
-            self.skip_operator("{");
-            while !self.eof()
+            let language = if s.trim_matches('"') == "C" { LanguageLinkage::C } else { LanguageLinkage::Cpp };
+            self.skip_token(); // skip "C" after "extern"
+
+            // extern "C" int x;
+            //            ^----------- here
+            //
+            // Parse it as ordinary `int x;`
+            if let Token::Identifier(_) = self.token()
             {
-                if let Token::Identifier(s) = self.token()
-                {
-                    self.add_declaration(s.into());
-                    self.skip_to_one_of_operators(vec![",", "}"]);
-                }
-                else if *self.token() == Token::Operator(",".into())
-                {
-                    self.skip_operator(",");
-                    continue;
-                }
-                else if *self.token() == Token::Operator("}".into())
-                {
-                    self.skip_operator("}");
-                    return;
-                }
-                else
-                {
-                    panic!("process_enum: Unexpected token {:?}", self.token())
-                }
+                self.specifiers.push(Specifier::Extern);
+                self.language_linkage = Some(language);
+                self.process_declaration()?;
+                return Ok(());
             }
-            panic!("process_enum: EOF");
+
+            // extern "C" { .... }
+            //            ^----------- here
+            self.skip_operator("{")?;
+            self.scopes.push(Scope::LinkageSpecification { language });
         }
         else
         {
-            panic!("process_enum: Unexpected token after `enum`: {:?}", self.token());
+            return Err(format!("unexpected {} after `extern`", describe_token(self.token())));
         }
+        Ok(())
     }
 
-    fn process_class_or_struct_without_semicolon(&mut self)
+    /// Check if the token at `offset` can start a module name or a header unit,
+    /// e.g. `std`, `:part` or `<vector>`, to tell `import std;` from `int import;`
+    fn is_module_name_start(&self, offset: usize) -> bool
     {
-        self.skip_token(); // skip `class`/`struct` keyword
-        if self.eof()
+        match self.tokens.get(self.pos + offset)
         {
-            panic!("process_class_or_struct: EOF while reading class/struct name");
+            Some(Token::Identifier(_)) | Some(Token::String(_)) => true,
+            Some(Token::Operator(s)) => s == "<" || s == ":" || s == ";",
+            _ => false,
         }
+    }
 
-        if let Token::Identifier(s) = self.token()
+    /// Handle `export` of a module:
+    ///
+    /// export module a;
+    /// export import b;
+    /// export int f();
+    /// export namespace c { ... }
+    /// export { ... }
+    fn process_export(&mut self) -> Result<(), String>
+    {
+        self.skip_identifier("export")?;
+        self.exported = true;
+        if self.is_operator("{")
         {
-            let s1 = s.clone();
-            self.skip_token();
-            if let Token::Identifier(s2) = self.token()
-            {
-                self.declarations.push(s2.to_string().clone());
-            }
-            else
-            {
-                self.declarations.push(s1.to_string().clone());
-            }
+            self.skip_operator("{")?;
+            self.scopes.push(Scope::Export);
+            return Ok(());
         }
+        self.process_declaration()
+    }
 
-        while (*self.token() != Token::Operator(";".to_string())) && (*self.token() != Token::Operator("{".to_string()))
-        {
-            if self.eof()
-            {
-                panic!("process_class_or_struct: EOF while parsing class/struct: ';' or '{{' needed");
-            }
-            self.skip_token();
-        }
+    /// Handle module declarations and imports:
+    ///
+    /// module;
+    /// module a.b:part;
+    /// module :private;
+    /// import a.b;
+    /// import :part;
+    /// import <vector>;
+    /// import "header.h";
+    fn process_module_or_import(&mut self) -> Result<(), String>
+    {
+        let kind = if self.is_identifier("module") { DeclarationKind::Module } else { DeclarationKind::Import };
+        self.skip_token();
 
-        if *self.token() == Token::Operator(";".to_string())
+        let start = self.pos;
+        while !self.is_operator(";") && !self.is_attribute_start()
         {
-            return;
+            self.assert_not_eof("expected `;`, found end of file")?;
+            self.skip_token();
         }
-        else if *self.token() == Token::Operator("{".to_string())
+        let name = join_tokens(&self.tokens[start..self.pos]);
+        while self.is_attribute_start()
         {
-            self.skip_bracket_pair("{", "}");
-            return;
+            self.read_attribute()?;
         }
-        else
+        self.skip_operator(";")?;
+
+        // The global module fragment `module;` and the private module fragment `module :private;`
+        // do not name a module
+        if name.is_empty() || (kind == DeclarationKind::Module && name == ":private")
         {
-            panic!("process_class_or_struct: unexpected token after class/struct name: {:?}", self.token());
+            return Ok(());
         }
-    }
 
-    fn process_class_or_struct(&mut self)
-    {
-        self.process_class_or_struct_without_semicolon();
-        self.skip_operator(";");
+        let mut declaration = Declaration::new(&name, kind);
+        declaration.linkage = Linkage::None;
+        declaration.is_exported = self.exported;
+        declaration.attributes = std::mem::take(&mut self.attributes);
+        self.declarations.push(declaration);
+        Ok(())
     }
 
-    fn process_typedef(&mut self)
+    /// Handle all forms of namespace declarations:
+    ///
+    /// namespace a { ... }
+    /// namespace a::b::c { ... }
+    /// namespace a::inline b { ... }
+    /// inline namespace a { ... }
+    /// namespace { ... }
+    /// namespace a = b::c;
+    fn process_namespace(&mut self) -> Result<(), String>
     {
-        self.skip_identifier("typedef");
-        if let Token::Identifier(identifier) = self.token()
+        // The `inline` keyword applies to the next namespace name
+        let mut is_inline = false;
+        if self.is_identifier("inline")
         {
-            match identifier.as_str()
+            self.skip_identifier("inline")?;
+            is_inline = true;
+        }
+        self.skip_identifier("namespace")?;
+
+        let mut names = Vec::<String>::new();
+        let mut anonymous = true;
+
+        loop
+        {
+            self.assert_not_eof("unexpected end of file in a namespace name")?;
+
+            match self.token().clone()
             {
-                "struct" | "class" => self.process_class_or_struct_without_semicolon(),
-                _ => {
-                    if let Some(declaration) = self.get_declaration()
+                Token::Identifier(s) if s == "inline" => {
+                    self.skip_token();
+                    is_inline = true;
+                },
+                _ if self.is_attribute_start() => self.read_attribute()?,
+                Token::Identifier(s) => {
+                    self.skip_token();
+                    anonymous = false;
+                    if !is_inline
                     {
-                        self.declarations.push(declaration);
+                        names.push(s);
                     }
-                    self.skip_operator(";");
-                }
+                    is_inline = false;
+                },
+                Token::Operator(s) if s == ":" => {
+                    // `::` is tokenized as two `:`
+                    self.skip_operator(":")?;
+                    self.skip_operator(":")?;
+                },
+                Token::Operator(s) if s == "=" => {
+                    // Namespace alias, e.g. `namespace fs = std::filesystem;`
+                    if names.len() != 1
+                    {
+                        return Err(format!("expected a single name in a namespace alias, found `{}`", names.join("::")));
+                    }
+                    self.add_declaration(&names[0], DeclarationKind::NamespaceAlias);
+                    self.skip_to_operator_inclusive(";")?;
+                    return Ok(());
+                },
+                Token::Operator(s) if s == "{" => {
+                    self.skip_operator("{")?;
+                    self.scopes.push(Scope::Namespace { names, anonymous, exported: self.exported });
+                    return Ok(());
+                },
+                token => return Err(format!("unexpected {} in a namespace definition", describe_token(&token))),
             }
         }
-        else
-        {
-            panic!("Process typedef: Identifier expected, {:?} found", self.token());
-        }
     }
 
-    fn process_extern(&mut self)
+    /// Process a single declaration at namespace or class scope
+    fn process_declaration(&mut self) -> Result<(), String>
     {
-        self.skip_identifier("extern");
-
-        if self.eof()
+        if self.is_attribute_start()
         {
-            panic!("process_extren: EOF after extern");
+            self.read_attribute()?;
+            return self.process_declaration();
         }
 
-        // extern int x;
-        //        ^-------- here
-        //
-        // Parse it as ordinary `int x;`
-        if let Token::Identifier(_) = self.token() {
-            return;
+        if let Token::Identifier(s) = self.token()
+        {
+            // C++ keywords are ordinary identifiers in C
+            let keyword = if self.dialect == Dialect::C && is_cpp_only_keyword(s) { "" } else { s.as_str() };
+            match keyword
+            {
+                "template" => {
+                    self.skip_identifier("template")?;
+                    if self.is_operator("<")
+                    {
+                        let parameters: Vec<Parameter> = self.read_parameter_list("<", ">")?
+                            .iter()
+                            .map(|parameter| parse_parameter(parameter, true))
+                            .collect();
+                        if parameters.is_empty()
+                        {
+                            self.specialization = Some(Specialization::Explicit);
+                        }
+                        self.template_parameters = Some(parameters);
+                    }
+                    else if let Some(index) = self.specifiers.iter().position(|s| *s == Specifier::Extern)
+                    {
+                        // extern template class A<int>;
+                        self.specifiers.remove(index);
+                        self.specialization = Some(Specialization::ExternInstantiation);
+                    }
+                    else
+                    {
+                        // template class A<int>;
+                        self.specialization = Some(Specialization::Instantiation);
+                    }
+                    if self.is_identifier("requires")
+                    {
+                        self.requires_clause = Some(self.read_requires_clause()?);
+                    }
+                    self.process_declaration()?;
+                },
+                "using" => self.process_using()?,
+                "concept" => self.process_concept()?,
+                "export" => self.process_export()?,
+                "module" | "import" if self.is_module_name_start(1) => self.process_module_or_import()?,
+                "enum" => self.process_enum()?,
+                "class" | "struct" | "union" => self.process_class_or_struct()?,
+                "typedef" => self.process_typedef()?,
+                "extern" => self.process_extern()?,
+                "namespace" => self.process_namespace()?,
+                "inline" if self.next_token() == Some(&Token::Identifier("namespace".into())) => self.process_namespace()?,
+                "friend" => {
+                    self.skip_identifier("friend")?;
+                    self.specifiers.push(Specifier::Friend);
+                    self.process_declaration()?;
+                },
+                "static_assert" | "_Static_assert" => {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive(";")?;
+                },
+                // asm("nop");
+                "asm" | "__asm" | "__asm__" => {
+                    self.skip_token();
+                    self.skip_to_operator_inclusive(";")?;
+                },
+                _ => self.process_variables_or_function()?,
+            }
         }
-
-        // extern "C" .....
-        //        ^------- here
-        if let Token::String(_) = self.token()
+        else if let Token::Operator(s) = self.token()
         {
-            self.skip_token(); // skip "C" after "extern"
-
-            // extern "C" int x;
-            //            ^----------- here
-            //
-            // Parse it as ordinary `int x;`
-            if let Token::Identifier(_) = self.token()
+            match s.as_str()
             {
-                return;
+                ";" => self.skip_operator(";")?,
+                // Destructor or a name starting with `::`
+                "~" | ":" => self.process_variables_or_function()?,
+                _ => return Err(format!("unexpected `{}`", s)),
             }
-
-            // extern "C" { .... }
-            //            ^----------- here
-            self.skip_operator("{");
-            self.closing_curly_brackets_expected += 1;
-            return;
         }
         else
         {
-            panic!("process_extren: unexpected token after `extern`: {:?}", self.token());
+            return Err(format!("unexpected {}", describe_token(self.token())));
         }
+        Ok(())
+    }
+
+    /// Record a problem at the current position
+    fn add_diagnostic(&mut self, message: String)
+    {
+        let line = match self.positions.get(self.pos).or(self.positions.last())
+        {
+            Some(position) => position.line,
+            None => 1,
+        };
+        self.diagnostics.push(Diagnostic { line, message });
     }
 
-    fn find_declarations(&mut self, file_content: &str)
+    /// Skip tokens up to and including the next `;` or balanced `}` (with an optional `;` after it)
+    ///
+    /// An unbalanced `}` closes the enclosing scope, so it is not skipped.
+    fn skip_to_recovery_point(&mut self)
     {
-        self.declarations = get_preprocessor_definitions(file_content);
-        self.tokens = filter_tokens(tokenize(&file_content));
+        let mut depth = 0;
 
         while !self.eof()
         {
-            if let Token::Identifier(s) = self.token()
+            match self.token()
             {
-                match s.as_str()
-                {
-                    "template" => {
-                        self.skip_identifier("template");
-                        self.skip_template_brackets();
-                    },
-                    "using" => self.process_using(),
-                    "enum" => self.process_enum(),
-                    "class" | "struct" => self.process_class_or_struct(),
-                    "typedef" => self.process_typedef(),
-                    "extern" => self.process_extern(),
-                    "namespace" => {
-                        self.skip_identifier("namespace");
-                        self.skip_token(); // skip namespace name
-                        self.skip_operator("{");
-                        self.closing_curly_brackets_expected += 1;
-                    },
-                    _ => {
-                        if let Some(declaration) = self.get_declaration()
+                Token::Operator(s) if s == ";" && depth == 0 => {
+                    self.skip_token();
+                    return;
+                },
+                Token::Operator(s) if s == "(" || s == "[" || s == "{" => depth += 1,
+                Token::Operator(s) if s == ")" || s == "]" => depth = std::cmp::max(depth, 1) - 1,
+                Token::Operator(s) if s == "}" => {
+                    if depth == 0
+                    {
+                        return;
+                    }
+                    depth -= 1;
+                    if depth == 0
+                    {
+                        self.skip_token();
+                        if self.is_operator(";")
                         {
-                            self.declarations.push(declaration);
+                            self.skip_token();
                         }
+                        return;
+                    }
+                },
+                _ => {},
+            }
+            self.skip_token();
+        }
+    }
 
-                        if let Token::Operator(s2) = &self.tokens[self.pos]
-                        {
-                            match s2.as_str()
-                            {
-                                ";" => {self.pos += 1},
-                                "=" => self.skip_to_operator_inclusive(";"),
-                                "(" => self.skip_function(),
-                                _ => panic!("Unexpected operator: {}", s2),
-                            }
-                        }
-                        else
-                        {
-                            panic!("find_declarations: Unexpected token {:?}", self.token());
-                        }
-                    },
-                }
+    /// Process a declaration, or skip it if it can't be parsed
+    ///
+    /// The problem is recorded as a diagnostic, and parsing resumes after the next `;` or balanced `}`
+    fn process_declaration_or_recover(&mut self)
+    {
+        let start = self.pos;
+        let scope_count = self.scopes.len();
+
+        if let Err(message) = self.process_declaration()
+        {
+            self.add_diagnostic(message);
+            self.scopes.truncate(scope_count);
+            self.pos = start;
+            self.skip_to_recovery_point();
+            if self.pos == start
+            {
+                self.skip_token();
             }
-            else if let Token::Operator(s) = &self.tokens[self.pos]
+        }
+    }
+
+    fn find_declarations(&mut self, file_content: &str, replacements: &BTreeMap<String, String>)
+    {
+        for definition in get_preprocessor_definitions(file_content)
+        {
+            self.add_declaration(&definition, DeclarationKind::Macro);
+        }
+        (self.tokens, self.positions) = filter_tokens(tokenize(file_content), replacements);
+
+        while !self.eof()
+        {
+            self.specifiers.clear();
+            self.attributes.clear();
+            self.template_parameters = None;
+            self.requires_clause = None;
+            self.exported = false;
+            self.language_linkage = None;
+            self.specialization = None;
+
+            if *self.token() == Token::Operator("}".into())
             {
-                match s.as_str()
+                if self.scopes.pop().is_none()
                 {
-                    "}" => {
-                        if self.closing_curly_brackets_expected > 0 {
-                            self.closing_curly_brackets_expected -= 1;
-                            self.skip_operator("}");
-                        }
-                        else {
-                            panic!("closing_curly_brackets_expected = 0, but }} found");
-                        }
-                    }
-                    ";" => self.skip_operator(";"),
-                    _ => panic!("Unexpected operator: {}", s),
+                    self.add_diagnostic("unexpected `}` without a matching `{`".into());
                 }
+                self.skip_token();
             }
             else
             {
-                panic!("Unexpected token: {:?}", self.token());
+                self.process_declaration_or_recover();
             }
         }
     }
 }
 
-pub fn find_declarations(file_content: &str) -> Vec<String>
+/// Find declarations and definitions in a C++ file, with their kinds and linkage
+///
+/// Declarations that can't be parsed are skipped, and the problems are reported as diagnostics.
+pub fn find_declarations_with_diagnostics(file_content: &str) -> (Vec<Declaration>, Vec<Diagnostic>)
+{
+    find_declarations_in_dialect(file_content, Dialect::Cpp)
+}
+
+/// Find declarations and definitions in a C or C++ file, see find_declarations_with_diagnostics
+///
+/// C files may contain K&R function definitions, and C++ keywords are ordinary identifiers in them.
+pub fn find_declarations_in_dialect(file_content: &str, dialect: Dialect) -> (Vec<Declaration>, Vec<Diagnostic>)
+{
+    find_declarations_with_config(file_content, &Config { dialect: Some(dialect), ..Config::default() })
+}
+
+/// Find declarations and definitions in a file, see find_declarations_with_diagnostics
+///
+/// Identifiers are skipped and replaced as configured, and the file is C++ unless the configuration says otherwise.
+pub fn find_declarations_with_config(file_content: &str, config: &Config) -> (Vec<Declaration>, Vec<Diagnostic>)
 {
     let mut d = DeclarationFinder {
+        dialect: config.dialect.unwrap_or(Dialect::Cpp),
         tokens: Vec::<Token>::new(),
+        positions: Vec::<TokenPosition>::new(),
         pos: 0,
-        declarations: Vec::<String>::new(),
-        closing_curly_brackets_expected: 0,
+        declarations: Vec::<Declaration>::new(),
+        scopes: Vec::<Scope>::new(),
+        access: None,
+        specifiers: Vec::<Specifier>::new(),
+        template_parameters: None,
+        requires_clause: None,
+        exported: false,
+        language_linkage: None,
+        specialization: None,
+        template_arguments: None,
+        attributes: Vec::<Attribute>::new(),
+        name_start: 0,
+        name_end: 0,
+        nested_declarator_start: None,
+        nested_function_parameters: None,
+        diagnostics: Vec::<Diagnostic>::new(),
     };
-    d.find_declarations(file_content);
-    d.declarations
+    d.find_declarations(file_content, &config.identifier_replacements());
+    (d.declarations, d.diagnostics)
+}
+
+/// Find declarations and definitions in a file, with their kinds and linkage
+///
+/// Declarations that can't be parsed are skipped.
+pub fn find_declarations_detailed(file_content: &str) -> Vec<Declaration>
+{
+    find_declarations_with_diagnostics(file_content).0
+}
+
+/// Find names of declarations and definitions in a file
+///
+/// Names are qualified with the enclosing namespaces, e.g. `a::b::x`.
+/// Class members, operator overloads, names brought by `using` from elsewhere,
+/// module declarations and imports are not included. Each name is listed once, even if it is
+/// declared several times, e.g. `void f();` and `void f() {}`, see find_declarations_detailed.
+pub fn find_declarations(file_content: &str) -> Vec<String>
+{
+    let mut res = Vec::<String>::new();
+    let declarations = find_declarations_detailed(file_content)
+        .into_iter()
+        .filter(|declaration| !declaration.is_operator())
+        .filter(|declaration| !matches!(declaration.kind,
+            DeclarationKind::UsingDeclaration | DeclarationKind::UsingEnum | DeclarationKind::UsingDirective
+                | DeclarationKind::Module | DeclarationKind::Import));
+    for declaration in declarations
+    {
+        if !res.contains(&declaration.name)
+        {
+            res.push(declaration.name);
+        }
+    }
+    res
 }
 
 #[cfg(test)]
@@ -802,6 +3613,14 @@ int main() {\n
         assert_eq!(find_declarations(input), vec!["main"]);
     }
 
+    #[test]
+    fn test_repeated_declarations() {
+        let input = "struct S; void h(); void h() {} int x; extern int x; struct S {};";
+        assert_eq!(find_declarations(input), vec!["S", "h", "x"]);
+        let names: Vec<String> = find_declarations_detailed(input).into_iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["S", "h", "h", "x", "x", "S"]);
+    }
+
     #[test]
     fn test_ignoring_preprocessor_directives() {
         let input = "\
@@ -1131,7 +3950,830 @@ bool operator==(const S& lhs, const S& rhs)
             enum struct F { f1, f2, };
             int main() {}
         ";
-        assert_eq!(find_declarations(input), vec!["A", "a1", "a2", "B", "b1", "b2", "C", "D", "E", "F", "main"]);
+        assert_eq!(find_declarations(input), vec!["A", "a1", "a2", "B", "b1", "b2", "C", "C::c1", "C::c2", "D", "D::d1", "D::d2",
+            "E", "E::e1", "E::e2", "F", "F::f1", "F::f2", "main"]);
+    }
+
+    #[test]
+    fn test_namespace() {
+        let input = "
+            namespace a {
+                int x;
+                namespace b {
+                    void f();
+                }
+            }
+            int main() {}
+        ";
+        assert_eq!(find_declarations(input), vec!["a::x", "a::b::f", "main"]);
+    }
+
+    #[test]
+    fn test_nested_namespace_definition() {
+        let input = "
+            namespace a::b::c {
+                struct S;
+            }
+            namespace a::inline b {
+                int y;
+            }
+        ";
+        assert_eq!(find_declarations(input), vec!["a::b::c::S", "a::y"]);
+    }
+
+    #[test]
+    fn test_inline_namespace() {
+        let input = "
+            namespace lib {
+                inline namespace v1 {
+                    inline int get() { return 1; }
+                }
+            }
+        ";
+        assert_eq!(find_declarations(input), vec!["lib::get"]);
+    }
+
+    #[test]
+    fn test_anonymous_namespace() {
+        let input = "
+            namespace {
+                int counter;
+                namespace detail {
+                    void helper();
+                }
+            }
+            int main() {}
+        ";
+        let declarations = find_declarations_detailed(input);
+        let names: Vec<_> = declarations.iter().map(|d| d.name.as_str()).collect();
+        let linkage: Vec<_> = declarations.iter().map(|d| d.linkage).collect();
+        assert_eq!(names, vec!["counter", "detail::helper", "main"]);
+        assert_eq!(linkage, vec![Linkage::Internal, Linkage::Internal, Linkage::External]);
+    }
+
+    #[test]
+    fn test_namespace_alias() {
+        let input = "
+            namespace fs = std::filesystem;
+            namespace a {
+                namespace c = ::b::c;
+            }
+        ";
+        let declarations = find_declarations_detailed(input);
+        assert_eq!(declarations, vec![
+            Declaration { linkage: Linkage::None, ..Declaration::new("fs", DeclarationKind::NamespaceAlias) },
+            Declaration { linkage: Linkage::None, ..Declaration::new("a::c", DeclarationKind::NamespaceAlias) },
+        ]);
+    }
+
+    fn describe_members(declaration: &Declaration) -> Vec<String>
+    {
+        declaration.members.iter().map(|member| member.to_string()).collect()
+    }
+
+    #[test]
+    fn test_class_members_access() {
+        let input = "
+            class A {
+                int a;
+            public:
+                int b;
+                void c();
+            protected:
+                static int d;
+            private:
+                int e, *f;
+            };
+            struct B {
+                int g;
+            };
+        ";
+        let declarations = find_declarations_detailed(input);
+        assert_eq!(describe_members(&declarations[0]), vec![
+            "A::a : int [private]",
+            "A::b : int [public]",
+            "A::c [public]",
+            "A::d : int [protected, static]",
+            "A::e : int [private]",
+            "A::f : int* [private]",
+        ]);
+        assert_eq!(describe_members(&declarations[1]), vec!["B::g : int [public]"]);
+        assert_eq!(declarations[0].members[2].kind, DeclarationKind::Function);
+        assert_eq!(declarations[0].members[3].kind, DeclarationKind::Variable);
+    }
+
+    #[test]
+    fn test_class_special_members() {
+        let input = "
+            namespace ns {
+            class A : public B {
+            public:
+                A() = default;
+                explicit A(int x) : B(x), value(x), other{x} {}
+                A(const A&) = delete;
+                virtual ~A();
+                virtual void f() const = 0;
+                void g() const override;
+                A& operator=(const A&) = default;
+                bool operator==(const A& rhs) const { return value == rhs.value; }
+                int operator()(int a) const;
+                explicit operator bool() const noexcept { return true; }
+                auto h() -> std::string;
+            private:
+                int value, other;
+            };
+            }
+        ";
+        let declarations = find_declarations_detailed(input);
+        assert_eq!(describe_members(&declarations[0]), vec![
+            "ns::A::A [public, = default]",
+            "ns::A::A [public]",
+            "ns::A::A [public, = delete]",
+            "ns::A::~A [public, virtual]",
+            "ns::A::f [public, virtual, = 0]",
+            "ns::A::g [public, override]",
+            "ns::A::operator= [public, = default]",
+            "ns::A::operator== [public]",
+            "ns::A::operator() [public]",
+            "ns::A::operator bool [public]",
+            "ns::A::h [public]",
+            "ns::A::value : int [private]",
+            "ns::A::other : int [private]",
+        ]);
+    }
+
+    #[test]
+    fn test_class_nested_types_and_friends() {
+        let input = "
+            class A {
+                struct Nested { int n; };
+                enum class Color { Red, Green };
+                using Ptr = A*;
+                friend class B;
+                friend void swap(A&, A&);
+                template <typename T> T get() const;
+                static_assert(sizeof(int) == 4, \"int\");
+                unsigned flags : 3;
+            };
+        ";
+        let declarations = find_declarations_detailed(input);
+        assert_eq!(describe_members(&declarations[0]), vec![
+            "A::Nested [private]",
+            "A::Color [private]",
+            "A::Color::Red [private]",
+            "A::Color::Green [private]",
+            "A::Ptr [private]",
+            "B [friend]",
+            "swap [friend]",
+            "A::get [private]",
+            "A::flags : unsigned [private]",
+        ]);
+        assert_eq!(describe_members(&declarations[0].members[0]), vec!["A::Nested::n : int [public]"]);
+    }
+
+    #[test]
+    fn test_operators_are_not_in_names() {
+        let input = "
+            struct S {
+                bool operator<(const S&) const;
+            };
+            bool operator==(const S&, const S&);
+        ";
+        let declarations = find_declarations_detailed(input);
+        assert_eq!(declarations[1].name, "operator==");
+        assert_eq!(find_declarations(input), vec!["S"]);
+    }
+
+    #[test]
+    fn test_out_of_class_definitions() {
+        let input = "
+            namespace ns {
+            void Foo::bar() {}
+            int Foo::counter = 0;
+            Foo::Foo() : x(1) {}
+            Foo::~Foo() {}
+            bool Foo::operator==(const Foo&) const { return true; }
+            template <typename T> std::vector<T> Bar<T>::get() { return {}; }
+            }
+        ";
+        let declarations = find_declarations_detailed(input);
+        let names: Vec<_> = declarations.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec![
+            "ns::Foo::bar",
+            "ns::Foo::counter",
+            "ns::Foo::Foo",
+            "ns::Foo::~Foo",
+            "ns::Foo::operator==",
+            "ns::Bar::get",
+        ]);
+        for declaration in &declarations
+        {
+            assert!(declaration.is_definition);
+        }
+        assert_eq!(declarations[0].class_name, Some("ns::Foo".into()));
+        assert_eq!(declarations[5].class_name, Some("ns::Bar".into()));
+    }
+
+    #[test]
+    fn test_split_qualified_name() {
+        assert_eq!(split_qualified_name("x"), ("", "x"));
+        assert_eq!(split_qualified_name("a::b::x"), ("a::b", "x"));
+        assert_eq!(split_qualified_name("a::B::operator std::string"), ("a::B", "operator std::string"));
+        assert_eq!(split_qualified_name("operator std::string"), ("", "operator std::string"));
+    }
+
+    fn prototypes(declarations: &[Declaration]) -> Vec<String>
+    {
+        let mut res = Vec::new();
+        for declaration in declarations
+        {
+            res.extend(declaration.prototype());
+            res.extend(prototypes(&declaration.members));
+        }
+        res
+    }
+
+    #[test]
+    fn test_function_prototypes() {
+        let input = "
+            int printf(const char * format, ...);
+            static inline unsigned long long hash(const std::string &s, std::map<int, int> m = {}, int a[10]);
+            void c_function(void);
+            void unnamed(int, struct S, std::vector<int>*);
+            constexpr auto square(int x) noexcept -> int { return x * x; }
+            template <typename T, int N = 3, class... Ts> T max(T a, Ts&&... rest);
+            char * const get(int x = a < b, int y = f(1, 2));
+            decltype(auto) forward_value() { return 1; }
+            inline decltype(x) * pointer(decltype(y) a);
+        ";
+        assert_eq!(prototypes(&find_declarations_detailed(input)), vec![
+            "int printf(const char* format, ...)",
+            "static inline unsigned long long hash(const std::string& s, std::map<int, int> m = {}, int a[10])",
+            "void c_function()",
+            "void unnamed(int, struct S, std::vector<int>*)",
+            "constexpr int square(int x) noexcept",
+            "template <typename T, int N = 3, class... Ts> T max(T a, Ts&&... rest)",
+            "char* const get(int x = a<b, int y = f(1, 2))",
+            "decltype(auto) forward_value()",
+            "inline decltype(x)* pointer(decltype(y) a)",
+        ]);
+    }
+
+    #[test]
+    fn test_member_function_prototypes() {
+        let input = "
+            struct A {
+                A(int x) : x(x) {}
+                virtual ~A() = default;
+                virtual int f() const & noexcept(false) = 0;
+                std::string&& g() && override;
+                explicit operator bool() const;
+                consteval static int h(int volatile * p);
+            };
+        ";
+        assert_eq!(prototypes(&find_declarations_detailed(input)), vec![
+            "A::A(int x)",
+            "virtual A::~A() = default",
+            "virtual int A::f() const & noexcept(false) = 0",
+            "std::string&& A::g() && override",
+            "A::operator bool() const",
+            "consteval static int A::h(int volatile* p)",
+        ]);
+    }
+
+    #[test]
+    fn test_function_try_blocks() {
+        let input = "
+            void f() try { } catch (...) { }
+            class C { C() try : a(1) {} catch (const std::exception& e) { throw; } catch (...) {} int a; };
+            auto g() -> int try { return 1; } catch (...) { return 0; }
+            int h();
+        ";
+        let (declarations, diagnostics) = find_declarations_with_diagnostics(input);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(prototypes(&declarations), vec!["void f()", "C::C()", "int g()", "int h()"]);
+        let definitions: Vec<bool> = declarations.iter().map(|d| d.is_definition).collect();
+        assert_eq!(definitions, vec![true, true, true, false]);
+        assert_eq!(describe_members(&declarations[1]), vec!["C::C [private]", "C::a : int [private]"]);
+    }
+
+    #[test]
+    fn test_function_signature() {
+        let declarations = find_declarations_detailed("template <> void f(const int& value = 0) volatile;");
+        assert_eq!(declarations[0].signature, Some(FunctionSignature {
+            template_parameters: Some(vec![]),
+            return_type: "void".into(),
+            parameters: vec![Parameter {
+                type_name: "const int&".into(),
+                name: Some("value".into()),
+                default_value: Some("0".into()),
+            }],
+            is_volatile: true,
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let input = "int a;
+            int b ) ;
+            namespace n {
+                struct S {
+                    int c;
+                    int d ] ;
+                    int e;
+                };
+                int ? f() { return 0; }
+            }
+            }
+            int g;
+        ";
+        let (declarations, diagnostics) = find_declarations_with_diagnostics(input);
+        let names: Vec<String> = declarations.iter().map(|d| d.name.clone()).collect();
+        assert_eq!(names, vec!["a", "n::S", "g"]);
+        assert_eq!(describe_members(&declarations[1]), vec!["n::S::c : int [public]", "n::S::e : int [public]"]);
+        let lines: Vec<usize> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 6, 9, 11]);
+        let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(messages, vec![
+            "line 2: unexpected `)` in a declaration",
+            "line 6: unexpected `]` in a declaration",
+            "line 9: unexpected `?` in a declaration",
+            "line 11: unexpected `}` without a matching `{`",
+        ]);
+        let (_, diagnostics) = find_declarations_with_diagnostics("void f(int a) const =");
+        assert_eq!(diagnostics[0].to_string(), "line 1: expected `default`, `delete` or `0` after `=`, found end of file");
+    }
+
+    #[test]
+    fn test_attributes() {
+        let input = "
+            [[nodiscard, deprecated(\"use g\")]] int f([[maybe_unused]] int x);
+            __declspec(dllexport) void g() __attribute__((noreturn));
+            alignas(16) char buffer [[gnu::aligned(16)]];
+            struct [[gnu::packed]] alignas(8) S {
+                [[no_unique_address]] int a;
+            } __attribute__((__deprecated__));
+            enum [[deprecated]] E { A [[deprecated]] = 1, B };
+            using T [[using gnu: hot, cold]] = int;
+        ";
+        let declarations = find_declarations_detailed(input);
+        let described: Vec<String> = declarations.iter().map(|d| d.to_string()).collect();
+        assert_eq!(described, vec![
+            "f [[nodiscard]] [[deprecated(\"use g\")]]",
+            "g __declspec(dllexport) __attribute__((noreturn))",
+            "buffer : char alignas(16) [[gnu::aligned(16)]]",
+            "S [[gnu::packed]] alignas(8) __attribute__((__deprecated__))",
+            "E [[deprecated]]",
+            "A = 1 [[deprecated]]",
+            "B",
+            "T [[gnu::hot]] [[gnu::cold]]",
+        ]);
+        assert_eq!(describe_members(&declarations[3]), vec!["S::a : int [[no_unique_address]] [public]"]);
+        assert_eq!(prototypes(&declarations[..1]), vec!["int f(int x)"]);
+
+        let deprecated: Vec<&str> = declarations.iter().filter(|d| d.has_attribute("deprecated")).map(|d| d.name.as_str()).collect();
+        assert_eq!(deprecated, vec!["f", "S", "E", "A"]);
+    }
+
+    #[test]
+    fn test_enum_forms() {
+        let input = "
+            enum Flags : unsigned char { Read = 1 << 0, Write = 1 << 1, All = Read | Write };
+            enum class Color : std::uint8_t;
+            enum Opaque : int;
+            enum { Anonymous = sizeof(int) * 2 };
+            typedef enum { X, Y } Point;
+            enum Flags flags;
+            namespace ns { enum struct E { A = -1 }; }
+        ";
+        let described: Vec<String> = find_declarations_detailed(input).iter().map(|d| d.to_string()).collect();
+        assert_eq!(described, vec![
+            "Flags : unsigned char",
+            "Read = 1 << 0",
+            "Write = 1 << 1",
+            "All = Read | Write",
+            "Color : std::uint8_t",
+            "Opaque : int",
+            "Anonymous = sizeof(int) * 2",
+            "X",
+            "Y",
+            "Point : <unnamed enum>",
+            "flags : enum Flags",
+            "ns::E",
+            "ns::E::A = -1",
+        ]);
+        let declarations = find_declarations_detailed(input);
+        assert!(declarations[0].is_definition);
+        assert!(!declarations[4].is_definition);
+    }
+
+    #[test]
+    fn test_class_declarators() {
+        let input = "
+            union U { int i; float f; } u;
+            struct { int x; } g;
+            struct S {} a, *b;
+            typedef struct { int y; } T, *PT;
+            class A final : public B {};
+            struct S s;
+            struct S *f(void);
+            enum { Off, On } state;
+            typedef unsigned long size_type;
+        ";
+        let described: Vec<(String, DeclarationKind, Option<String>)> = find_declarations_detailed(input)
+            .into_iter()
+            .map(|d| (d.name, d.kind, d.type_name))
+            .collect();
+        assert_eq!(described, vec![
+            ("U".into(), DeclarationKind::Union, None),
+            ("u".into(), DeclarationKind::Variable, Some("U".into())),
+            ("g".into(), DeclarationKind::Variable, Some("<unnamed struct>".into())),
+            ("S".into(), DeclarationKind::Struct, None),
+            ("a".into(), DeclarationKind::Variable, Some("S".into())),
+            ("b".into(), DeclarationKind::Variable, Some("S*".into())),
+            ("T".into(), DeclarationKind::Typedef, Some("<unnamed struct>".into())),
+            ("PT".into(), DeclarationKind::Typedef, Some("<unnamed struct>*".into())),
+            ("A".into(), DeclarationKind::Class, None),
+            ("s".into(), DeclarationKind::Variable, Some("struct S".into())),
+            ("f".into(), DeclarationKind::Function, None),
+            ("Off".into(), DeclarationKind::Enumerator, None),
+            ("On".into(), DeclarationKind::Enumerator, None),
+            ("state".into(), DeclarationKind::Variable, Some("<unnamed enum>".into())),
+            ("size_type".into(), DeclarationKind::Typedef, Some("unsigned long".into())),
+        ]);
+
+        let members: Vec<String> = find_declarations_detailed(input)[0].members.iter().map(|d| d.to_string()).collect();
+        assert_eq!(members, vec!["U::i : int [public]", "U::f : float [public]"]);
+        assert_eq!(prototypes(&find_declarations_detailed("struct S *f(void);")), vec!["struct S* f()"]);
+    }
+
+    #[test]
+    fn test_using_forms() {
+        let input = "
+            using Int = int;
+            template <class T> using Vec = std::vector<T>;
+            using std::swap, ::size_t;
+            namespace ns {
+                using namespace std::chrono;
+                using enum Color;
+            }
+            struct D : Base<int> {
+                using typename Base<int>::value_type;
+                using Base<int>::Base;
+                using Base<int>::operator=;
+                using Ptr [[deprecated]] = D*;
+            };
+            template <class... Ts> struct Overloaded : Ts... { using Ts::operator()...; };
+        ";
+        let declarations = find_declarations_detailed(input);
+        let described: Vec<(String, DeclarationKind, Option<String>)> = declarations
+            .iter()
+            .map(|d| (d.name.clone(), d.kind.clone(), d.target.clone()))
+            .collect();
+        assert_eq!(described[..7], vec![
+            ("Int".into(), DeclarationKind::TypeAlias, Some("int".into())),
+            ("Vec".into(), DeclarationKind::TypeAlias, Some("std::vector<T>".into())),
+            ("swap".into(), DeclarationKind::UsingDeclaration, Some("std::swap".into())),
+            ("size_t".into(), DeclarationKind::UsingDeclaration, Some("size_t".into())),
+            ("ns::chrono".into(), DeclarationKind::UsingDirective, Some("std::chrono".into())),
+            ("ns::Color".into(), DeclarationKind::UsingEnum, Some("Color".into())),
+            ("D".into(), DeclarationKind::Struct, None),
+        ]);
+        assert_eq!(declarations[1].template_parameters, Some(vec![Parameter {
+            type_name: "class".into(),
+            name: Some("T".into()),
+            default_value: None,
+        }]));
+        assert_eq!(describe_members(&declarations[6]), vec![
+            "using Base<int>::value_type [public]",
+            "using Base<int>::Base [public]",
+            "using Base<int>::operator= [public]",
+            "D::Ptr [[deprecated]] [public]",
+        ]);
+        assert_eq!(describe_members(&declarations[7]), vec!["using Ts::operator() [public]"]);
+        assert_eq!(declarations[4].to_string(), "using namespace std::chrono");
+        assert_eq!(find_declarations(input), vec!["Int", "Vec", "D", "Overloaded"]);
+    }
+
+    #[test]
+    fn test_concepts_and_constraints() {
+        let input = "
+            template <typename T> concept Sortable = requires (T a) { a < a; { a.size() } -> std::same_as<int>; };
+            template <typename T> requires Sortable<T> && (sizeof(T) > 1) void sort(T& t);
+            template <typename T> requires true void g(T t) requires std::integral<T> { if constexpr (requires { t.x; }) {} }
+            void f(std::integral auto x);
+            template <class T> requires std::copyable<T> || requires { typename T::type; } class Box { T t; };
+            template <std::integral T> struct W {};
+            int after;
+        ";
+        let declarations = find_declarations_detailed(input);
+        assert_eq!(find_declarations(input), vec!["Sortable", "sort", "g", "f", "Box", "W", "after"]);
+        assert_eq!(declarations[0].kind, DeclarationKind::Concept);
+        assert_eq!(declarations[0].initializer.as_deref(), Some("requires (T a) { a < a; { a.size() } -> std::same_as<int>; }"));
+        assert_eq!(prototypes(&declarations), vec![
+            "template <typename T> requires Sortable<T> && (sizeof(T) > 1) void sort(T& t)",
+            "template <typename T> requires (true) && (std::integral<T>) void g(T t)",
+            "void f(std::integral auto x)",
+        ]);
+        assert_eq!(declarations[4].requires_clause.as_deref(), Some("std::copyable<T> || requires { typename T::type; }"));
+        assert_eq!(declarations[5].template_parameters.as_ref().unwrap()[0].type_name, "std::integral");
+    }
+
+    #[test]
+    fn test_modules() {
+        let input = "
+            module;
+            #include <cassert>
+            export module shapes.core:geometry;
+            import std;
+            import <vector>;
+            export import :detail;
+            export int area(int w, int h);
+            export { struct Point { int x; }; int g; }
+            export namespace shapes { class Circle {}; }
+            int helper();
+            module :private;
+            int import;
+        ";
+        let described: Vec<String> = find_declarations_detailed(input).iter().map(|d| d.to_string()).collect();
+        assert_eq!(described, vec![
+            "module shapes.core:geometry [exported]",
+            "import std",
+            "import <vector>",
+            "import :detail [exported]",
+            "area [exported]",
+            "Point [exported]",
+            "g : int [exported]",
+            "shapes::Circle [exported]",
+            "helper",
+            "import : int",
+        ]);
+        assert_eq!(find_declarations(input), vec!["area", "Point", "g", "shapes::Circle", "helper", "import"]);
+    }
+
+    #[test]
+    fn test_initializers() {
+        let input = "
+            auto f = [](int x) { return x; };
+            std::vector<int> v{1, 2};
+            int a[] = { {1}, {2} };
+            auto g = [&, y = 1]<typename T>(T t) mutable noexcept -> int { return [=] { return t; }(); }, h = 2;
+            std::map<int, std::function<void()>> m = { {1, [] {}} };
+            auto p = std::pair<int, int>{1, 2}, q = std::less<>{};
+            bool b = x < y, c = z > 1;
+            constexpr auto k = [] { return 1; }();
+            int e = values[0];
+            struct S { std::function<void()> cb = [this] { run(); }; int after; };
+        ";
+        let described: Vec<String> = find_declarations_detailed(input).iter().map(|d| d.to_string()).collect();
+        assert_eq!(described, vec![
+            "f : auto [lambda]",
+            "v : std::vector<int>",
+            "a : int[]",
+            "g : auto [lambda]",
+            "h : auto",
+            "m : std::map<int, std::function<void()>>",
+            "p : auto",
+            "q : auto",
+            "b : bool",
+            "c : bool",
+            "k : auto [internal linkage, constexpr]",
+            "e : int",
+            "S",
+        ]);
+        let members: Vec<String> = find_declarations_detailed(input)[12].members.iter().map(|d| d.to_string()).collect();
+        assert_eq!(members, vec!["S::cb : std::function<void()> [public, lambda]", "S::after : int [public]"]);
+    }
+
+    #[test]
+    fn test_template_specializations() {
+        let input = "
+            template <typename T> struct Hash;
+            template <> struct Hash<int> { int v; };
+            template <typename T> struct Hash<T*> {};
+            template <typename T> void f(T);
+            template <> void f<int>(int);
+            template class std::vector<int>;
+            extern template class std::vector<std::pair<int, int>>;
+            template void f<char>(char);
+            extern template int g<int>(int);
+            template <> int Foo<int>::bar() { return 1; }
+            template <> constexpr bool is_v<int> = true;
+            std::vector<int> operator+(A, B);
+        ";
+        let described: Vec<String> = find_declarations_detailed(input).iter().map(|d| d.to_string()).collect();
+        assert_eq!(described, vec![
+            "Hash",
+            "Hash<int> [explicit specialisation]",
+            "Hash<T*> [partial specialisation]",
+            "f",
+            "f<int> [explicit specialisation]",
+            "std::vector<int> [explicit instantiation]",
+            "std::vector<std::pair<int, int>> [extern explicit instantiation]",
+            "f<char> [explicit instantiation]",
+            "g<int> [extern explicit instantiation]",
+            "Foo::bar [explicit specialisation]",
+            "is_v<int> : bool [explicit specialisation, constexpr]",
+            "operator+",
+        ]);
+        assert_eq!(prototypes(&find_declarations_detailed(input)), vec![
+            "template <typename T> void f(T)",
+            "template <> void f<int>(int)",
+            "template void f<char>(char)",
+            "extern template int g<int>(int)",
+            "template <> int Foo::bar()",
+            "std::vector<int> operator+(A, B)",
+        ]);
+    }
+
+    #[test]
+    fn test_declarators() {
+        let input = "
+            void (*fp)(int, char);
+            int arr[10][20];
+            int (*pa)[10];
+            int (&ref)[10] = x;
+            int Foo::*pm;
+            void (Foo::*pmf)(int) const;
+            typedef void (*handler_t)(int);
+            typedef int matrix[3][3], func(int);
+            char *(*getters[4])(void);
+            int (*make(int))(double);
+            int a, *b, c[2], (*d)(void);
+            struct S { void (*callback)(void*) noexcept; int bits[4]; };
+        ";
+        let declarations = find_declarations_detailed(input);
+        let described: Vec<(String, DeclarationKind, Option<String>)> = declarations
+            .iter()
+            .map(|d| (d.name.clone(), d.kind.clone(), d.type_name.clone()))
+            .collect();
+        assert_eq!(described, vec![
+            ("fp".into(), DeclarationKind::Variable, Some("void (*)(int, char)".into())),
+            ("arr".into(), DeclarationKind::Variable, Some("int[10][20]".into())),
+            ("pa".into(), DeclarationKind::Variable, Some("int (*)[10]".into())),
+            ("ref".into(), DeclarationKind::Variable, Some("int (&)[10]".into())),
+            ("pm".into(), DeclarationKind::Variable, Some("int Foo::*".into())),
+            ("pmf".into(), DeclarationKind::Variable, Some("void (Foo::*)(int) const".into())),
+            ("handler_t".into(), DeclarationKind::Typedef, Some("void (*)(int)".into())),
+            ("matrix".into(), DeclarationKind::Typedef, Some("int[3][3]".into())),
+            ("func".into(), DeclarationKind::Typedef, Some("int(int)".into())),
+            ("getters".into(), DeclarationKind::Variable, Some("char* (*[4])(void)".into())),
+            ("make".into(), DeclarationKind::Function, None),
+            ("a".into(), DeclarationKind::Variable, Some("int".into())),
+            ("b".into(), DeclarationKind::Variable, Some("int*".into())),
+            ("c".into(), DeclarationKind::Variable, Some("int[2]".into())),
+            ("d".into(), DeclarationKind::Variable, Some("int (*)(void)".into())),
+            ("S".into(), DeclarationKind::Struct, None),
+        ]);
+        assert_eq!(declarations[10].prototype().unwrap(), "int (*make(int))(double)");
+        assert_eq!(declarations[3].to_string(), "ref : int (&)[10]");
+        let members: Vec<Option<String>> = declarations[15].members.iter().map(|d| d.type_name.clone()).collect();
+        assert_eq!(members, vec![Some("void (*)(void*) noexcept".into()), Some("int[4]".into())]);
+    }
+
+    #[test]
+    fn test_typeof_specifiers() {
+        let input = "
+            decltype(x) y;
+            decltype(x) *p, q[2];
+            decltype(f(1, (2)))::type t;
+            typeof(int) v;
+            __typeof__(x) w = 1;
+            struct S { decltype(x) m; typeof(int) n; };
+            int typeof;
+        ";
+        let (declarations, diagnostics) = find_declarations_with_diagnostics(input);
+        assert_eq!(diagnostics, vec![]);
+        let described: Vec<String> = declarations.iter().map(|d| d.to_string()).collect();
+        assert_eq!(described, vec![
+            "y : decltype(x)",
+            "p : decltype(x)*",
+            "q : decltype(x)[2]",
+            "t : decltype(f(1, (2)))::type",
+            "v : typeof(int)",
+            "w : __typeof__(x)",
+            "S",
+            "typeof : int",
+        ]);
+        assert_eq!(describe_members(&declarations[6]), vec!["S::m : decltype(x) [public]", "S::n : typeof(int) [public]"]);
+    }
+
+    #[test]
+    fn test_c_dialect() {
+        let input = r#"
+            int add(a, b, n)
+                int a;
+                char *b;
+            {
+                return a + n;
+            }
+            struct flags { unsigned ready : 1; unsigned : 3; int mode : 2; };
+            _Static_assert(sizeof(int) == 4, "int");
+            int v = _Generic(1, int: 1, default: 0);
+            int *q = (int[]){1, 2, 3};
+            __asm__("nop");
+            int r __asm__("real_r");
+            static int counter;
+            int class, template;
+        "#;
+        let (declarations, diagnostics) = find_declarations_in_dialect(input, Dialect::C);
+        assert_eq!(diagnostics, vec![]);
+        let names: Vec<&str> = declarations.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["add", "flags", "v", "q", "r", "counter", "class", "template"]);
+        assert_eq!(declarations[0].prototype().unwrap(), "int add(int a, char* b, int n)");
+        let members: Vec<(&str, Option<Access>)> = declarations[1].members.iter().map(|d| (d.name.as_str(), d.access)).collect();
+        assert_eq!(members, vec![("flags::ready", None), ("flags::mode", None)]);
+        assert_eq!(declarations[4].type_name, Some("int".into()));
+        assert_eq!(declarations[4].linkage, Linkage::External);
+        assert_eq!(declarations[4].language_linkage, Some(LanguageLinkage::C));
+        assert_eq!(declarations[5].linkage, Linkage::Internal);
+    }
+
+    #[test]
+    fn test_identifier_replacements() {
+        let input = "
+            class A FINAL : public B {};
+            MYLIB_API void f();
+            MYLIB_INLINE int g() { return 0; }
+            class EXPORT C final {};
+            int final = 1;
+        ";
+        let mut config = Config::default();
+        config.replacements.insert("FINAL".into(), "final".into());
+        config.replacements.insert("MYLIB_API".into(), "".into());
+        config.skip_identifiers.push("MYLIB_INLINE".into());
+        let (declarations, diagnostics) = find_declarations_with_config(input, &config);
+        assert_eq!(diagnostics, vec![]);
+        let names: Vec<&str> = declarations.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "f", "g", "C", "final"]);
+        assert_eq!(declarations[2].prototype().unwrap(), "int g()");
     }
 
+    #[test]
+    fn test_linkage_and_storage() {
+        let input = r#"
+            static int counter;
+            const int limit = 10;
+            extern const int shared;
+            constexpr double pi = 3.14;
+            inline constexpr int answer = 42;
+            const char* name = "x";
+            char* const fixed = nullptr;
+            thread_local int tls;
+            static void helper() {}
+            extern "C" {
+                int c_function(int x);
+                extern "C++" void cpp_function();
+            }
+            extern "C" int c_variable;
+            struct S { int member; static int shared_member; void f(); };
+            typedef int T;
+        "#;
+        let declarations = find_declarations_detailed(input);
+        let described: Vec<(&str, Linkage, Option<LanguageLinkage>)> = declarations
+            .iter()
+            .flat_map(|d| std::iter::once(d).chain(d.members.iter()))
+            .map(|d| (d.name.as_str(), d.linkage, d.language_linkage))
+            .collect();
+        assert_eq!(described, vec![
+            ("counter", Linkage::Internal, None),
+            ("limit", Linkage::Internal, None),
+            ("shared", Linkage::External, Some(LanguageLinkage::Cpp)),
+            ("pi", Linkage::Internal, None),
+            ("answer", Linkage::External, Some(LanguageLinkage::Cpp)),
+            ("name", Linkage::External, Some(LanguageLinkage::Cpp)),
+            ("fixed", Linkage::Internal, None),
+            ("tls", Linkage::External, Some(LanguageLinkage::Cpp)),
+            ("helper", Linkage::Internal, None),
+            ("c_function", Linkage::External, Some(LanguageLinkage::C)),
+            ("cpp_function", Linkage::External, Some(LanguageLinkage::Cpp)),
+            ("c_variable", Linkage::External, Some(LanguageLinkage::C)),
+            ("S", Linkage::External, None),
+            ("S::member", Linkage::None, None),
+            ("S::shared_member", Linkage::External, None),
+            ("S::f", Linkage::External, None),
+            ("T", Linkage::None, None),
+        ]);
+
+        let storage: Vec<(&str, Option<StorageDuration>)> = declarations
+            .iter()
+            .flat_map(|d| std::iter::once(d).chain(d.members.iter()))
+            .filter(|d| d.kind == DeclarationKind::Variable)
+            .map(|d| (d.name.as_str(), d.storage_duration))
+            .collect();
+        assert_eq!(storage, vec![
+            ("counter", Some(StorageDuration::Static)),
+            ("limit", Some(StorageDuration::Static)),
+            ("shared", Some(StorageDuration::Static)),
+            ("pi", Some(StorageDuration::Static)),
+            ("answer", Some(StorageDuration::Static)),
+            ("name", Some(StorageDuration::Static)),
+            ("fixed", Some(StorageDuration::Static)),
+            ("tls", Some(StorageDuration::Thread)),
+            ("c_variable", Some(StorageDuration::Static)),
+            ("S::member", None),
+            ("S::shared_member", Some(StorageDuration::Static)),
+        ]);
+    }
 }
@@ -0,0 +1,177 @@
+/// Number of unchanged lines shown around changes
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit
+{
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Find edits turning `old` into `new`, using the longest common subsequence of lines
+///
+/// Lines common to the beginning and the end are skipped first, so that small changes of long files are cheap.
+fn find_edits(old: &[&str], new: &[&str]) -> Vec<Edit>
+{
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    // lengths[i][j] is the length of the longest common subsequence of old_middle[i..] and new_middle[j..]
+    let mut lengths = vec![vec![0usize; new_middle.len() + 1]; old_middle.len() + 1];
+    for i in (0..old_middle.len()).rev()
+    {
+        for j in (0..new_middle.len()).rev()
+        {
+            lengths[i][j] = if old_middle[i] == new_middle[j]
+            {
+                lengths[i + 1][j + 1] + 1
+            }
+            else
+            {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut res = vec![Edit::Equal; prefix];
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() || j < new_middle.len()
+    {
+        if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j]
+        {
+            res.push(Edit::Equal);
+            i += 1;
+            j += 1;
+        }
+        else if j == new_middle.len() || (i < old_middle.len() && lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            res.push(Edit::Delete);
+            i += 1;
+        }
+        else
+        {
+            res.push(Edit::Insert);
+            j += 1;
+        }
+    }
+    res.extend(vec![Edit::Equal; suffix]);
+    res
+}
+
+/// Append a line to a diff, marking a missing new line at the end of the file like `diff` does
+fn push_line(res: &mut String, prefix: char, line: &str)
+{
+    res.push(prefix);
+    res.push_str(line);
+    if !line.ends_with('\n')
+    {
+        res.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// Make a unified diff of two versions of a file, or an empty string if they are the same
+pub fn unified_diff(file_name: &str, old: &str, new: &str) -> String
+{
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let edits = find_edits(&old_lines, &new_lines);
+    if edits.iter().all(|e| *e == Edit::Equal)
+    {
+        return String::new();
+    }
+
+    // Positions of the edits in the old and the new file
+    let mut positions = Vec::<(usize, usize)>::new();
+    let (mut i, mut j) = (0, 0);
+    for edit in &edits
+    {
+        positions.push((i, j));
+        match edit
+        {
+            Edit::Equal => { i += 1; j += 1; },
+            Edit::Delete => i += 1,
+            Edit::Insert => j += 1,
+        }
+    }
+
+    let mut res = format!("--- {}\n+++ {}\n", file_name, file_name);
+    let changes: Vec<usize> = (0..edits.len()).filter(|&k| edits[k] != Edit::Equal).collect();
+    let mut k = 0;
+    while k < changes.len()
+    {
+        // Changes closer than twice the context go to the same hunk
+        let start = changes[k].saturating_sub(CONTEXT_LINES);
+        let mut last = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] - last <= 2 * CONTEXT_LINES
+        {
+            k += 1;
+            last = changes[k];
+        }
+        let end = (last + 1 + CONTEXT_LINES).min(edits.len());
+        k += 1;
+
+        let hunk = &edits[start..end];
+        let old_count = hunk.iter().filter(|e| **e != Edit::Insert).count();
+        let new_count = hunk.iter().filter(|e| **e != Edit::Delete).count();
+        let (old_start, new_start) = positions[start];
+        // Empty ranges start at the line before them
+        let old_start = if old_count == 0 { old_start } else { old_start + 1 };
+        let new_start = if new_count == 0 { new_start } else { new_start + 1 };
+        res.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count));
+
+        for (edit, (i, j)) in hunk.iter().zip(&positions[start..end])
+        {
+            match edit
+            {
+                Edit::Equal => push_line(&mut res, ' ', old_lines[*i]),
+                Edit::Delete => push_line(&mut res, '-', old_lines[*i]),
+                Edit::Insert => push_line(&mut res, '+', new_lines[*j]),
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff()
+    {
+        assert_eq!(unified_diff("a.cpp", "x\n", "x\n"), "");
+
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "0\n1\n2\n3\n4\n5\n6\n7\n8\n10\n11\n12\n";
+        assert_eq!(unified_diff("a.cpp", old, new), "\
+--- a.cpp
++++ a.cpp
+@@ -1,3 +1,4 @@
++0
+ 1
+ 2
+ 3
+@@ -6,7 +7,6 @@
+ 6
+ 7
+ 8
+-9
+ 10
+ 11
+ 12
+");
+        assert_eq!(unified_diff("a.cpp", "a\nb", "a\nc"), "\
+--- a.cpp
++++ a.cpp
+@@ -1,2 +1,2 @@
+ a
+-b
+\\ No newline at end of file
++c
+\\ No newline at end of file
+");
+    }
+}
@@ -0,0 +1,563 @@
+use crate::iwyu::Mapping;
+use crate::toml;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Version of the database format understood by HeaderDb::parse
+const FORMAT_VERSION: i64 = 1;
+
+/// Names of the optional databases embedded at build time, see HeaderDb::builtin
+pub const BUILTIN_DBS: &[&str] = &["posix", "linux", "boost", "qt", "abseil"];
+
+/// A version of the C++ standard, ordered by time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Standard
+{
+    Cpp98,
+    Cpp03,
+    Cpp11,
+    Cpp14,
+    Cpp17,
+    Cpp20,
+    Cpp23,
+    Cpp26,
+}
+
+impl Standard
+{
+    /// Get a standard by its name as given to compilers, e.g. `c++17`, `c++1z` or `gnu++17`
+    pub fn from_name(name: &str) -> Option<Standard>
+    {
+        let name = name.to_lowercase();
+        let version = name.strip_prefix("c++").or(name.strip_prefix("gnu++"))?;
+        match version
+        {
+            "98" => Some(Standard::Cpp98),
+            "03" => Some(Standard::Cpp03),
+            "11" | "0x" => Some(Standard::Cpp11),
+            "14" | "1y" => Some(Standard::Cpp14),
+            "17" | "1z" => Some(Standard::Cpp17),
+            "20" | "2a" => Some(Standard::Cpp20),
+            "23" | "2b" => Some(Standard::Cpp23),
+            "26" | "2c" => Some(Standard::Cpp26),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Standard
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let version = match self
+        {
+            Standard::Cpp98 => "98",
+            Standard::Cpp03 => "03",
+            Standard::Cpp11 => "11",
+            Standard::Cpp14 => "14",
+            Standard::Cpp17 => "17",
+            Standard::Cpp20 => "20",
+            Standard::Cpp23 => "23",
+            Standard::Cpp26 => "26",
+        };
+        write!(f, "C++{}", version)
+    }
+}
+
+/// Standards a header or a symbol was introduced, deprecated and removed in, None if it never was
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Availability
+{
+    // None for C++98
+    pub introduced: Option<Standard>,
+    pub deprecated: Option<Standard>,
+    pub removed: Option<Standard>,
+}
+
+impl Availability
+{
+    /// Fill in what is not known from `other`, e.g. from the header declaring a symbol
+    fn or(self, other: Availability) -> Availability
+    {
+        Availability {
+            introduced: self.introduced.or(other.introduced),
+            deprecated: self.deprecated.or(other.deprecated),
+            removed: self.removed.or(other.removed),
+        }
+    }
+}
+
+/// Symbols provided by headers, read from a database file like `data/standard_headers.toml`
+///
+/// A header provides its own symbols and, recursively, all symbols of the headers it includes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderDb
+{
+    // Symbols declared by each header itself
+    symbols: BTreeMap<String, Vec<String>>,
+    // Headers whose symbols a header provides as well, e.g. `istream` and `ostream` for `iostream`
+    includes: BTreeMap<String, Vec<String>>,
+    // Headers whose symbols are named as in the global namespace, e.g. `printf` of `cstdio` or
+    // `boost::optional` of `boost/optional.hpp`, rather than as in `namespace std`
+    global_headers: Vec<String>,
+    // Standards headers were introduced, deprecated and removed in, e.g. C++20 for `span`
+    header_availability: BTreeMap<String, Availability>,
+    // Standards symbols were introduced, deprecated and removed in, if different from their header,
+    // by header and symbol, e.g. `utility` and `move`. No header stands for every header declaring it.
+    symbol_availability: BTreeMap<(Option<String>, String), Availability>,
+    // Headers to include instead of private headers, e.g. `vector` for `bits/stl_vector.h`
+    public_headers: BTreeMap<String, Vec<String>>,
+}
+
+impl HeaderDb
+{
+    /// Parse database file content
+    ///
+    /// Included headers are not checked, as they may come from another database, see HeaderDb::validate.
+    pub fn parse(content: &str) -> Result<HeaderDb, String>
+    {
+        let mut db = HeaderDb::default();
+        let mut global_namespace = false;
+        for (key, value) in toml::parse(content)?
+        {
+            match key.as_str()
+            {
+                "version" => {
+                    if value != toml::Value::Integer(FORMAT_VERSION)
+                    {
+                        return Err(format!("unsupported version {:?}, expected {}", value, FORMAT_VERSION));
+                    }
+                },
+                "c_headers" => {
+                    db.global_headers = value.as_string_array().ok_or("`c_headers` must be an array of strings")?;
+                },
+                "global_namespace" => {
+                    global_namespace = value.as_bool().ok_or("`global_namespace` must be a boolean")?;
+                },
+                "headers" => {
+                    for (header, symbols) in value.as_table().ok_or("`headers` must be a table")?
+                    {
+                        let symbols = symbols.as_str().ok_or(format!("symbols of `{}` must be a string", header))?;
+                        db.symbols.insert(header.clone(), symbols.split_whitespace().map(String::from).collect());
+                    }
+                },
+                "includes" => {
+                    for (header, included) in value.as_table().ok_or("`includes` must be a table")?
+                    {
+                        let included = included.as_string_array().ok_or(format!("includes of `{}` must be an array of strings", header))?;
+                        db.includes.insert(header.clone(), included);
+                    }
+                },
+                "headers_introduced" | "headers_deprecated" | "headers_removed"
+                | "symbols_introduced" | "symbols_deprecated" | "symbols_removed" => {
+                    for (name, names) in value.as_table().ok_or(format!("`{}` must be a table", key))?
+                    {
+                        let standard = Standard::from_name(name).ok_or(format!("unknown standard `{}` in `{}`", name, key))?;
+                        let names = names.as_str().ok_or(format!("`{}` of `{}` must be a string", name, key))?;
+                        for name in names.split_whitespace()
+                        {
+                            let entry = if key.starts_with("headers")
+                            {
+                                db.header_availability.entry(name.into()).or_default()
+                            }
+                            else
+                            {
+                                db.symbol_availability.entry(parse_header_symbol(name)?).or_default()
+                            };
+                            match key.rsplit('_').next()
+                            {
+                                Some("introduced") => entry.introduced = Some(standard),
+                                Some("deprecated") => entry.deprecated = Some(standard),
+                                _ => entry.removed = Some(standard),
+                            }
+                        }
+                    }
+                },
+                _ => return Err(format!("unknown key `{}`", key)),
+            }
+        }
+        if global_namespace
+        {
+            db.global_headers = db.headers().into_iter().map(String::from).collect();
+        }
+        Ok(db)
+    }
+
+    /// Read a database file
+    pub fn load(path: &Path) -> Result<HeaderDb, String>
+    {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        HeaderDb::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// The database of the standard C and C++ headers, embedded at build time
+    pub fn standard() -> HeaderDb
+    {
+        HeaderDb::parse(include_str!("../data/standard_headers.toml")).expect("invalid data/standard_headers.toml")
+    }
+
+    /// An optional database embedded at build time by its name, see BUILTIN_DBS
+    pub fn builtin(name: &str) -> Option<HeaderDb>
+    {
+        let content = match name
+        {
+            "posix" => include_str!("../data/posix_headers.toml"),
+            "linux" => include_str!("../data/linux_headers.toml"),
+            "boost" => include_str!("../data/boost_headers.toml"),
+            "qt" => include_str!("../data/qt_headers.toml"),
+            "abseil" => include_str!("../data/abseil_headers.toml"),
+            _ => return None,
+        };
+        Some(HeaderDb::parse(content).unwrap_or_else(|e| panic!("invalid data/{}_headers.toml: {}", name, e)))
+    }
+
+    /// Add headers of another database, replacing headers with the same names
+    pub fn merge(&mut self, other: HeaderDb)
+    {
+        let replaced: Vec<String> = other.headers().into_iter().map(String::from).collect();
+        for header in &replaced
+        {
+            self.symbols.remove(header);
+            self.includes.remove(header);
+        }
+        self.symbols.extend(other.symbols);
+        self.includes.extend(other.includes);
+        self.header_availability.extend(other.header_availability);
+        self.symbol_availability.extend(other.symbol_availability);
+        self.public_headers.extend(other.public_headers);
+        for header in other.global_headers
+        {
+            if !self.global_headers.contains(&header)
+            {
+                self.global_headers.push(header);
+            }
+        }
+    }
+
+    /// Add an include-what-you-use mapping, see iwyu::parse_mappings
+    ///
+    /// Only headers included with `<>` are in the database, mappings of other headers are ignored.
+    /// Mappings of unknown headers are ignored as well, as their other symbols aren't known, and so
+    /// are mappings which would make a header include itself.
+    pub fn add_mapping(&mut self, mapping: &Mapping)
+    {
+        let name = |header: &str| header.strip_prefix('<').and_then(|h| h.strip_suffix('>')).map(String::from);
+        match mapping
+        {
+            Mapping::Include { header, private, public } => {
+                let (header, public) = match (name(header), name(public))
+                {
+                    (Some(header), Some(public)) => (header, public),
+                    _ => return,
+                };
+                if !self.contains(&header) || header == public || self.included_headers(&header).contains(&public.as_str())
+                {
+                    return;
+                }
+                let included = self.includes.entry(public.clone()).or_default();
+                if !included.contains(&header)
+                {
+                    included.push(header.clone());
+                }
+                if *private
+                {
+                    let public_headers = self.public_headers.entry(header).or_default();
+                    if !public_headers.contains(&public)
+                    {
+                        public_headers.push(public);
+                    }
+                }
+            },
+            Mapping::Symbol { symbol, public } => {
+                let public = match name(public)
+                {
+                    Some(public) if self.contains(&public) => public,
+                    _ => return,
+                };
+                // Symbols of headers declaring `namespace std` are named as inside it
+                let symbol = if self.has_global_symbols(&public) { symbol } else { symbol.strip_prefix("std::").unwrap_or(symbol) };
+                let symbols = self.symbols.entry(public).or_default();
+                if !symbols.iter().any(|s| s == symbol)
+                {
+                    symbols.push(symbol.into());
+                }
+            },
+            Mapping::Ref(_) => {},
+        }
+    }
+
+    /// Check that all included headers are known and that headers don't include themselves
+    pub fn validate(&self) -> Result<(), String>
+    {
+        for (header, included) in &self.includes
+        {
+            if let Some(unknown) = included.iter().find(|h| !self.contains(h))
+            {
+                return Err(format!("`{}` includes unknown header `{}`", header, unknown));
+            }
+            if self.included_headers(header).contains(&header.as_str())
+            {
+                return Err(format!("`{}` includes itself", header));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if symbols of a header are named as in the global namespace, as in the C library,
+    /// rather than as in `namespace std`
+    pub fn has_global_symbols(&self, header: &str) -> bool
+    {
+        self.global_headers.iter().any(|h| h == header)
+    }
+
+    pub fn contains(&self, header: &str) -> bool
+    {
+        self.symbols.contains_key(header) || self.includes.contains_key(header)
+    }
+
+    /// All headers in the database, sorted by name
+    pub fn headers(&self) -> Vec<&str>
+    {
+        let mut res: Vec<&str> = self.symbols.keys().chain(self.includes.keys()).map(|h| h.as_str()).collect();
+        res.sort();
+        res.dedup();
+        res
+    }
+
+    /// Headers included by `header` directly or indirectly, in the order they are found
+    pub(crate) fn included_headers(&self, header: &str) -> Vec<&str>
+    {
+        let mut res = Vec::<&str>::new();
+        let mut pending: Vec<&str> = vec![header];
+        while let Some(current) = pending.pop()
+        {
+            for included in self.includes.get(current).into_iter().flatten()
+            {
+                if !res.contains(&included.as_str())
+                {
+                    res.push(included);
+                    pending.push(included);
+                }
+            }
+        }
+        res
+    }
+
+    /// Headers declaring a symbol themselves, not through the headers they include, sorted by name
+    pub fn headers_declaring(&self, symbol: &str) -> Vec<&str>
+    {
+        self.symbols
+            .iter()
+            .filter(|(_, symbols)| symbols.iter().any(|s| s == symbol))
+            .map(|(header, _)| header.as_str())
+            .collect()
+    }
+
+    /// Headers to include for the symbols of a header: the header itself, or the public headers
+    /// to include instead of a private one, which may be none
+    pub fn public_headers<'a>(&'a self, header: &'a str) -> Vec<&'a str>
+    {
+        match self.public_headers.get(header)
+        {
+            Some(public) => public.iter().map(String::as_str).collect(),
+            None => vec![header],
+        }
+    }
+
+    /// Name of a C library header in C, e.g. `stdio.h` for `cstdio`
+    pub fn c_name<'a>(&'a self, header: &'a str) -> &'a str
+    {
+        self.includes
+            .iter()
+            .find(|(h, included)| h.ends_with(".h") && included.len() == 1 && included[0] == header)
+            .map(|(h, _)| h.as_str())
+            .unwrap_or(header)
+    }
+
+    /// Standards a header was introduced, deprecated and removed in
+    pub fn header_availability(&self, header: &str) -> Availability
+    {
+        self.header_availability.get(header).copied().unwrap_or_default()
+    }
+
+    /// Standards a symbol declared by `header` was introduced, deprecated and removed in
+    ///
+    /// What is not known about the symbol itself is taken from the header.
+    pub fn symbol_availability(&self, symbol: &str, header: &str) -> Availability
+    {
+        let availability = |header: Option<&str>| self.symbol_availability.get(&(header.map(String::from), symbol.to_string())).copied();
+        availability(Some(header))
+            .or_else(|| availability(None))
+            .unwrap_or_default()
+            .or(self.header_availability(header))
+    }
+
+    /// Symbols provided by a header, including the symbols of the headers it includes
+    pub fn symbols(&self, header: &str) -> Vec<&str>
+    {
+        let mut res = Vec::<&str>::new();
+        for h in std::iter::once(header).chain(self.included_headers(header))
+        {
+            for symbol in self.symbols.get(h).into_iter().flatten()
+            {
+                if !res.contains(&symbol.as_str())
+                {
+                    res.push(symbol);
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Split a symbol into the header it is declared by and its name, e.g. `<utility>move`,
+/// with no header for a bare symbol
+fn parse_header_symbol(name: &str) -> Result<(Option<String>, String), String>
+{
+    match name.strip_prefix('<')
+    {
+        Some(rest) => match rest.split_once('>')
+        {
+            Some((header, symbol)) if !header.is_empty() && !symbol.is_empty() => Ok((Some(header.into()), symbol.into())),
+            _ => Err(format!("`{}` must be a header in <> followed by a symbol", name)),
+        },
+        None => Ok((None, name.into())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_inheritance()
+    {
+        let input = r#"
+            version = 1
+
+            [headers]
+            ios = "ios_base"
+            istream = "istream ws"
+            ostream = "ostream endl"
+            iostream = "cin cout"
+
+            [includes]
+            istream = ["ios"]
+            ostream = ["ios"]
+            iostream = ["istream", "ostream"]
+        "#;
+        let db = HeaderDb::parse(input).unwrap();
+        assert_eq!(db.validate(), Ok(()));
+        assert_eq!(db.symbols("iostream"), vec!["cin", "cout", "istream", "ws", "ostream", "endl", "ios_base"]);
+        assert_eq!(db.symbols("unknown"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_merge_and_validate()
+    {
+        let mut db = HeaderDb::parse("[headers]\na = \"x\"\nb = \"y\"\n[includes]\nb = [\"a\"]").unwrap();
+        db.merge(HeaderDb::parse("[headers]\nb = \"z\"\nc = \"w\"\n[includes]\nc = [\"d\"]").unwrap());
+        assert_eq!(db.headers(), vec!["a", "b", "c"]);
+        assert_eq!(db.symbols("b"), vec!["z"]);
+        assert_eq!(db.validate(), Err("`c` includes unknown header `d`".into()));
+
+        let cyclic = HeaderDb::parse("[includes]\na = [\"b\"]\nb = [\"a\"]").unwrap();
+        assert_eq!(cyclic.validate(), Err("`a` includes itself".into()));
+        assert_eq!(HeaderDb::parse("version = 2"), Err("unsupported version Integer(2), expected 1".into()));
+    }
+
+    #[test]
+    fn test_mappings()
+    {
+        let mut db = HeaderDb::parse("[headers]
+\"bits/vec.h\" = \"vector\"\ncstddef = \"size_t\"").unwrap();
+        let include = |header: &str, private, public: &str| Mapping::Include { header: header.into(), private, public: public.into() };
+        db.add_mapping(&include("<bits/vec.h>", true, "<vector>"));
+        db.add_mapping(&include("<bits/unknown.h>", true, "<list>"));
+        db.add_mapping(&include("\"bits/vec.h\"", true, "\"vec.h\""));
+        db.add_mapping(&include("<vector>", false, "<bits/vec.h>"));
+        db.add_mapping(&Mapping::Symbol { symbol: "std::nullptr_t".into(), public: "<cstddef>".into() });
+        assert_eq!(db.headers(), vec!["bits/vec.h", "cstddef", "vector"]);
+        assert_eq!(db.symbols("vector"), vec!["vector"]);
+        assert_eq!(db.symbols("cstddef"), vec!["size_t", "nullptr_t"]);
+        assert_eq!(db.public_headers("bits/vec.h"), vec!["vector"]);
+        assert_eq!(db.public_headers("cstddef"), vec!["cstddef"]);
+        assert_eq!(db.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_builtin()
+    {
+        let mut db = HeaderDb::standard();
+        for name in BUILTIN_DBS
+        {
+            let builtin = HeaderDb::builtin(name).unwrap();
+            assert!(builtin.headers().iter().all(|h| builtin.has_global_symbols(h) && !db.contains(h)), "{}", name);
+            db.merge(builtin);
+        }
+        assert_eq!(db.validate(), Ok(()));
+        assert!(db.symbols("sys/socket.h").contains(&"socket"));
+        assert!(db.symbols("boost/algorithm/string.hpp").contains(&"boost::trim"));
+        assert!(db.symbols("QtCore/QString").contains(&"QString"));
+        assert_eq!(HeaderDb::builtin("unknown"), None);
+    }
+
+    #[test]
+    fn test_availability()
+    {
+        let input = r#"
+            [headers]
+            memory = "unique_ptr make_unique auto_ptr"
+            span = "span"
+            utility = "move"
+            algorithm = "move"
+
+            [headers_introduced]
+            "c++20" = "span"
+
+            [symbols_introduced]
+            "c++11" = "unique_ptr <utility>move"
+            "c++1y" = "make_unique"
+
+            [symbols_deprecated]
+            "c++11" = "auto_ptr"
+
+            [symbols_removed]
+            "c++17" = "auto_ptr"
+        "#;
+        let db = HeaderDb::parse(input).unwrap();
+        assert_eq!(db.symbol_availability("make_unique", "memory").introduced, Some(Standard::Cpp14));
+        assert_eq!(db.symbol_availability("span", "span").introduced, Some(Standard::Cpp20));
+        assert_eq!(db.symbol_availability("auto_ptr", "memory"), Availability {
+            introduced: None,
+            deprecated: Some(Standard::Cpp11),
+            removed: Some(Standard::Cpp17),
+        });
+        assert_eq!(db.symbol_availability("move", "utility").introduced, Some(Standard::Cpp11));
+        assert_eq!(db.symbol_availability("move", "algorithm").introduced, None);
+        assert_eq!(db.header_availability("memory"), Availability::default());
+        assert_eq!(HeaderDb::parse("[headers_removed]\n\"c++4\" = \"a\""), Err("unknown standard `c++4` in `headers_removed`".into()));
+        assert_eq!(HeaderDb::parse("[symbols_removed]\n\"c++11\" = \"<utility\""), Err("`<utility` must be a header in <> followed by a symbol".into()));
+        assert_eq!(Standard::from_name("gnu++17").map(|s| s.to_string()), Some("C++17".into()));
+        assert!(Standard::Cpp98 < Standard::Cpp03 && Standard::Cpp20 < Standard::Cpp26);
+    }
+
+    #[test]
+    fn test_standard()
+    {
+        let db = HeaderDb::standard();
+        assert_eq!(db.validate(), Ok(()));
+        assert!(db.symbols("iostream").contains(&"cout"));
+        assert!(db.symbols("iostream").contains(&"endl"));
+        assert_eq!(db.symbols("assert.h"), vec!["assert"]);
+        assert!(db.has_global_symbols("cstdio") && db.has_global_symbols("stdio.h") && !db.has_global_symbols("vector"));
+        assert!(db.headers().iter().all(|h| !db.symbols(h).contains(&"")));
+        assert_eq!(db.headers_declaring("optional"), vec!["optional"]);
+        assert_eq!(db.headers_declaring("iter_swap"), vec!["algorithm", "iterator"]);
+        assert_eq!(db.c_name("cstdio"), "stdio.h");
+        assert_eq!(db.c_name("vector"), "vector");
+    }
+}
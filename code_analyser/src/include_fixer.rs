@@ -0,0 +1,355 @@
+/// Header of an `#include` line with its brackets, e.g. `<vector>` or `"config.h"`
+///
+/// Spaces around `#` and a trailing comment are allowed, other lines give None.
+pub(crate) fn included_header(line: &str) -> Option<&str>
+{
+    let rest = line.trim_start().strip_prefix('#')?.trim_start().strip_prefix("include")?.trim_start();
+    let close = match rest.chars().next()?
+    {
+        '<' => '>',
+        '"' => '"',
+        _ => return None,
+    };
+    let end = rest[1..].find(close)? + 2;
+    let after = rest[end..].trim();
+    if !(after.is_empty() || after.starts_with("//") || after.starts_with("/*"))
+    {
+        return None;
+    }
+    Some(&rest[..end])
+}
+
+/// Header of an `import <...>;` line of a header unit with its brackets, e.g. `<vector>`
+///
+/// Exported header units, e.g. `export import <vector>;`, give None, as other files may rely on them.
+fn imported_header(line: &str) -> Option<&str>
+{
+    let rest = line.trim_start().strip_prefix("import")?.trim_start();
+    if !rest.starts_with('<')
+    {
+        return None;
+    }
+    let end = rest.find('>')? + 1;
+    let after = rest[end..].trim_start().strip_prefix(';')?.trim();
+    if !(after.is_empty() || after.starts_with("//") || after.starts_with("/*"))
+    {
+        return None;
+    }
+    Some(&rest[..end])
+}
+
+/// Header of an `#include` or an `import` line, see included_header and imported_header
+fn included_or_imported_header(line: &str) -> Option<&str>
+{
+    included_header(line).or_else(|| imported_header(line))
+}
+
+/// Name of a header included or imported with `<>`, e.g. `vector` for `#include <vector>`
+fn standard_header(line: &str) -> Option<&str>
+{
+    included_or_imported_header(line)?.strip_prefix('<')?.strip_suffix('>')
+}
+
+fn is_blank(line: &str) -> bool
+{
+    line.trim().is_empty()
+}
+
+fn line_ending(content: &str) -> &'static str
+{
+    if content.contains("\r\n") { "\r\n" } else { "\n" }
+}
+
+/// Check if a line is only a comment, or the end of a comment spanning several lines
+fn is_comment(line: &str) -> bool
+{
+    let trimmed = line.trim();
+    trimmed.starts_with("//") || trimmed.starts_with("/*") || (trimmed.ends_with("*/") && !trimmed.contains("/*"))
+}
+
+/// Find where the comment directly above line `end` starts, `end` if there is none
+fn find_comment_start(lines: &[&str], end: usize) -> usize
+{
+    let mut start = end;
+    while start > 0 && is_comment(lines[start - 1])
+    {
+        start -= 1;
+        // Lines of a comment spanning several lines up to its beginning
+        if !lines[start].trim_start().starts_with("//")
+        {
+            while start > 0 && !lines[start].contains("/*")
+            {
+                start -= 1;
+            }
+        }
+    }
+    start
+}
+
+/// Remove `#include <...>` and `import <...>;` lines of the given standard headers
+///
+/// Other lines, including comments next to the removed ones, are kept, except the comment directly
+/// above a group of includes which is removed as a whole. One of the blank lines separating such
+/// a group from its neighbours is removed as well.
+pub fn remove_includes(content: &str, headers: &[String]) -> String
+{
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut is_removed: Vec<bool> = lines
+        .iter()
+        .map(|line| standard_header(line).is_some_and(|h| headers.iter().any(|header| header == h)))
+        .collect();
+
+    let mut start = 0;
+    while start < lines.len()
+    {
+        let end = (start..lines.len()).find(|&i| included_or_imported_header(lines[i]).is_none()).unwrap_or(lines.len());
+        if end > start && is_removed[start..end].iter().all(|r| *r)
+        {
+            let comment_start = find_comment_start(&lines, start);
+            is_removed[comment_start..start].fill(true);
+        }
+        start = end + 1;
+    }
+
+    let mut res = Vec::<&str>::new();
+    let mut removed = false;
+    for (line, is_removed) in lines.iter().zip(is_removed)
+    {
+        if is_removed
+        {
+            removed = true;
+            continue;
+        }
+        if removed && is_blank(line) && res.last().is_none_or(|last| is_blank(last))
+        {
+            continue;
+        }
+        removed = false;
+        res.push(line);
+    }
+    res.concat()
+}
+
+/// Find where the lines of a group of includes start and end, preferring groups of standard headers only
+fn find_include_group(lines: &[&str]) -> Option<(usize, usize)>
+{
+    let mut groups = Vec::<(usize, usize)>::new();
+    for (i, line) in lines.iter().enumerate()
+    {
+        if included_header(line).is_none()
+        {
+            continue;
+        }
+        match groups.last_mut()
+        {
+            Some((_, end)) if *end == i => *end = i + 1,
+            _ => groups.push((i, i + 1)),
+        }
+    }
+
+    let is_standard = |&(start, end): &(usize, usize)| lines[start..end].iter().all(|l| standard_header(l).is_some());
+    let has_standard = |&(start, end): &(usize, usize)| lines[start..end].iter().any(|l| standard_header(l).is_some());
+    groups.iter().find(|g| is_standard(g)).or(groups.iter().find(|g| has_standard(g))).copied()
+}
+
+/// Find where new includes go in a file without includes: after leading comments, `#pragma once`
+/// and the include guard
+fn find_preamble_end(lines: &[&str]) -> usize
+{
+    let mut in_comment = false;
+    let mut res = 0;
+    for (i, line) in lines.iter().enumerate()
+    {
+        let trimmed = line.trim();
+        if in_comment || trimmed.starts_with("/*")
+        {
+            in_comment = !trimmed.contains("*/");
+        }
+        else if !(trimmed.is_empty()
+            || trimmed.starts_with("//")
+            || trimmed == "#pragma once"
+            || trimmed.starts_with("#ifndef")
+            || (trimmed.starts_with("#define") && i > 0 && lines[i - 1].trim().starts_with("#ifndef")))
+        {
+            return res;
+        }
+        if !trimmed.is_empty() && !in_comment
+        {
+            res = i + 1;
+        }
+    }
+    res
+}
+
+/// Insert `#include <...>` lines of the given standard headers
+///
+/// Headers go to the first group of includes of standard headers, in sorted order if the group is
+/// sorted, or at its end otherwise. Without such a group, a new one is added after the other includes,
+/// or after the leading comments and the include guard of a file without includes.
+pub fn insert_includes(content: &str, headers: &[String]) -> String
+{
+    if headers.is_empty()
+    {
+        return content.into();
+    }
+
+    let new_line = line_ending(content);
+    let mut lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if let Some(last) = lines.last() && !last.ends_with('\n')
+    {
+        // Every line is terminated while inserting, the missing new line is dropped at the end
+        return insert_includes(&format!("{}{}", content, new_line), headers)
+            .strip_suffix(new_line)
+            .unwrap()
+            .into();
+    }
+
+    let mut new_lines: Vec<String> = headers.iter().map(|h| format!("#include <{}>{}", h, new_line)).collect();
+    new_lines.sort();
+
+    match find_include_group(&lines)
+    {
+        Some((start, end)) if (start..end).any(|i| standard_header(lines[i]).is_some()) => {
+            let group: Vec<&str> = lines[start..end].iter().filter_map(|l| standard_header(l)).collect();
+            let is_sorted = group.windows(2).all(|w| w[0] <= w[1]);
+            let last_standard = (start..end).rev().find(|&i| standard_header(lines[i]).is_some()).unwrap();
+            let mut res = Vec::<String>::new();
+            let mut pending = new_lines.iter().peekable();
+            for (i, line) in lines.iter().enumerate()
+            {
+                if is_sorted && let Some(current) = standard_header(line) && (start..end).contains(&i)
+                {
+                    while let Some(new) = pending.next_if(|new| standard_header(new).unwrap() < current)
+                    {
+                        res.push(new.clone());
+                    }
+                }
+                res.push(line.to_string());
+                if i == last_standard
+                {
+                    res.extend(pending.by_ref().cloned());
+                }
+            }
+            res.concat()
+        },
+        _ => {
+            let last_include = lines.iter().rposition(|l| included_header(l).is_some());
+            let position = match last_include
+            {
+                Some(i) => i + 1,
+                None => find_preamble_end(&lines),
+            };
+            let mut block = Vec::<String>::new();
+            if position > 0 && !is_blank(lines[position - 1])
+            {
+                block.push(new_line.into());
+            }
+            block.extend(new_lines);
+            if lines.get(position).is_some_and(|l| !is_blank(l))
+            {
+                block.push(new_line.into());
+            }
+            lines.splice(position..position, block.iter().map(String::as_str));
+            lines.concat()
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn strings(headers: &[&str]) -> Vec<String>
+    {
+        headers.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn test_included_header()
+    {
+        assert_eq!(included_header("#include <vector>\n"), Some("<vector>"));
+        assert_eq!(included_header("  #  include \"a.h\" // comment"), Some("\"a.h\""));
+        assert_eq!(included_header("#include <vector> int x;"), None);
+        assert_eq!(included_header("#define include <vector>"), None);
+        assert_eq!(standard_header("#include \"a.h\""), None);
+        assert_eq!(standard_header("import <vector>; // comment"), Some("vector"));
+        assert_eq!(standard_header("export import <vector>;"), None);
+        assert_eq!(standard_header("import <vector> int x;"), None);
+        assert_eq!(standard_header("important <vector>;"), None);
+    }
+
+    #[test]
+    fn test_remove_includes()
+    {
+        let input = "\
+// Containers
+#include <vector>
+#include <map> // for the cache
+
+// Streams
+#include <iostream>
+
+#include \"config.h\"
+";
+        assert_eq!(remove_includes(input, &strings(&["map"])), "\
+// Containers
+#include <vector>
+
+// Streams
+#include <iostream>
+
+#include \"config.h\"
+");
+        assert_eq!(remove_includes(input, &strings(&["iostream"])), "\
+// Containers
+#include <vector>
+#include <map> // for the cache
+
+#include \"config.h\"
+");
+        assert_eq!(remove_includes("#include <map>\n\nint x;\n", &strings(&["map"])), "int x;\n");
+
+        let code_above = "int x; /* x */\n#include <map>\n";
+        assert_eq!(remove_includes(code_above, &strings(&["map"])), "int x; /* x */\n");
+
+        let block_comment = "int x;\n\n/* Containers,\n   sorted */\n#include <map>\n#include <set>\n";
+        assert_eq!(remove_includes(block_comment, &strings(&["map"])), "int x;\n\n/* Containers,\n   sorted */\n#include <set>\n");
+        assert_eq!(remove_includes(block_comment, &strings(&["map", "set"])), "int x;\n\n");
+
+        let header_units = "import std;\n\n// Containers\nimport <map>;\n#include <set>\n\nint x;\n";
+        assert_eq!(remove_includes(header_units, &strings(&["map"])), "import std;\n\n// Containers\n#include <set>\n\nint x;\n");
+        assert_eq!(remove_includes(header_units, &strings(&["map", "set"])), "import std;\n\nint x;\n");
+    }
+
+    #[test]
+    fn test_insert_includes()
+    {
+        let input = "\
+#include \"config.h\"
+
+#include <map>
+#include <vector>
+
+int x;
+";
+        assert_eq!(insert_includes(input, &strings(&["string", "algorithm"])), "\
+#include \"config.h\"
+
+#include <algorithm>
+#include <map>
+#include <string>
+#include <vector>
+
+int x;
+");
+
+        let unsorted = "#include <vector>\r\n#include <map>\r\nint x;";
+        assert_eq!(insert_includes(unsorted, &strings(&["string"])), "#include <vector>\r\n#include <map>\r\n#include <string>\r\nint x;");
+
+        let without_standard = "#include \"config.h\"\nint x;\n";
+        assert_eq!(insert_includes(without_standard, &strings(&["string"])), "#include \"config.h\"\n\n#include <string>\n\nint x;\n");
+
+        let without_includes = "// Header\n#ifndef A_H\n#define A_H\nint x;\n#endif\n";
+        assert_eq!(insert_includes(without_includes, &strings(&["string"])), "// Header\n#ifndef A_H\n#define A_H\n\n#include <string>\n\nint x;\n#endif\n");
+    }
+}
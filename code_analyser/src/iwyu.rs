@@ -0,0 +1,416 @@
+use crate::include_fixer::included_header;
+
+use std::fs;
+use std::path::Path;
+
+/// include-what-you-use pragmas of a file, e.g. `#include <vector> // IWYU pragma: keep`
+#[derive(Debug, Default, PartialEq)]
+pub struct Pragmas
+{
+    // Headers included with `keep`, in brackets, e.g. `<vector>`
+    pub kept: Vec<String>,
+    // Headers included with `export` or between `begin_exports` and `end_exports`, in brackets
+    pub exported: Vec<String>,
+    // The file is a private header, `// IWYU pragma: private`
+    pub is_private: bool,
+    // Header to include instead of the file, in brackets, `// IWYU pragma: private, include "public.h"`
+    pub public_header: Option<String>,
+}
+
+impl Pragmas
+{
+    /// Check if an include of a header in brackets must stay even if it looks unused
+    pub fn keeps(&self, header: &str) -> bool
+    {
+        self.kept.iter().chain(&self.exported).any(|h| h == header)
+    }
+}
+
+/// Text of a pragma in a comment of a line, e.g. `keep` for `#include <vector> // IWYU pragma: keep`
+fn pragma(line: &str) -> Option<&str>
+{
+    let start = line.find("IWYU pragma:")?;
+    if !(line[..start].contains("//") || line[..start].contains("/*"))
+    {
+        return None;
+    }
+    let text = &line[start + "IWYU pragma:".len()..];
+    Some(text.split("*/").next().unwrap().trim())
+}
+
+/// Find the IWYU pragmas of a file
+///
+/// Only the pragmas `keep`, `export`, `begin_exports`, `end_exports` and `private` are supported.
+pub fn parse_pragmas(file_content: &str) -> Pragmas
+{
+    let mut res = Pragmas::default();
+    let mut in_exports = false;
+    for line in file_content.lines()
+    {
+        let pragma = pragma(line);
+        match pragma
+        {
+            Some("begin_exports") => in_exports = true,
+            Some("end_exports") => in_exports = false,
+            Some(p) if p == "private" || p.starts_with("private,") => {
+                res.is_private = true;
+                res.public_header = p["private".len()..]
+                    .trim_start_matches(',')
+                    .trim()
+                    .strip_prefix("include")
+                    .map(|h| h.trim().to_string())
+                    .filter(|h| !h.is_empty());
+            },
+            _ => {},
+        }
+
+        if let Some(header) = included_header(line)
+        {
+            if pragma == Some("keep")
+            {
+                res.kept.push(header.into());
+            }
+            else if pragma == Some("export") || in_exports
+            {
+                res.exported.push(header.into());
+            }
+        }
+    }
+    res
+}
+
+/// An entry of an include-what-you-use mapping file, with headers in brackets, e.g. `<vector>`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mapping
+{
+    // Including `public` provides the symbols of `header`, which is not to be included directly if private
+    Include { header: String, private: bool, public: String },
+    // Including `public` provides `symbol`, e.g. `std::size_t`
+    Symbol { symbol: String, public: String },
+    // Another mapping file, relative to the directory of this one
+    Ref(String),
+}
+
+/// A value of a mapping file, which is written in a subset of YAML
+#[derive(Debug)]
+enum ImpValue
+{
+    Scalar(String),
+    List(Vec<ImpValue>),
+    Map(Vec<(String, ImpValue)>),
+}
+
+struct ImpParser
+{
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl ImpParser
+{
+    fn peek(&self) -> Option<char>
+    {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char>
+    {
+        let ch = self.peek();
+        if ch == Some('\n')
+        {
+            self.line += 1;
+        }
+        self.pos += 1;
+        ch
+    }
+
+    fn error(&self, message: &str) -> String
+    {
+        format!("line {}: {}", self.line, message)
+    }
+
+    fn skip_whitespace_and_comments(&mut self)
+    {
+        while let Some(ch) = self.peek()
+        {
+            if ch == '#'
+            {
+                while !matches!(self.peek(), Some('\n') | None)
+                {
+                    self.next();
+                }
+            }
+            else if ch.is_whitespace()
+            {
+                self.next();
+            }
+            else
+            {
+                return;
+            }
+        }
+    }
+
+    fn read_scalar(&mut self) -> Result<String, String>
+    {
+        let mut res = String::new();
+        match self.peek()
+        {
+            Some('"') => {
+                self.next();
+                loop
+                {
+                    match self.next()
+                    {
+                        Some('"') => return Ok(res),
+                        Some('\\') => match self.next()
+                        {
+                            Some(ch) => res.push(ch),
+                            None => return Err(self.error("unterminated string")),
+                        },
+                        Some('\n') | None => return Err(self.error("unterminated string")),
+                        Some(ch) => res.push(ch),
+                    }
+                }
+            },
+            Some('\'') => {
+                self.next();
+                loop
+                {
+                    match self.next()
+                    {
+                        // A quote is escaped by doubling it
+                        Some('\'') if self.peek() == Some('\'') => { self.next(); res.push('\''); },
+                        Some('\'') => return Ok(res),
+                        Some('\n') | None => return Err(self.error("unterminated string")),
+                        Some(ch) => res.push(ch),
+                    }
+                }
+            },
+            _ => {
+                // A plain scalar ends before a separator, `:` only separates when followed by a space
+                while let Some(ch) = self.peek()
+                {
+                    let is_separator = matches!(ch, ',' | '[' | ']' | '{' | '}' | '#' | '\n')
+                        || (ch == ':' && self.chars.get(self.pos + 1).is_none_or(|next| next.is_whitespace()));
+                    if is_separator
+                    {
+                        break;
+                    }
+                    res.push(ch);
+                    self.next();
+                }
+                let res = res.trim().to_string();
+                if res.is_empty()
+                {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(res)
+            },
+        }
+    }
+
+    /// Read the elements of a list or a map after the opening bracket, up to the closing one
+    fn read_elements<T>(&mut self, close: char, mut read: impl FnMut(&mut Self) -> Result<T, String>) -> Result<Vec<T>, String>
+    {
+        let mut res = Vec::new();
+        loop
+        {
+            self.skip_whitespace_and_comments();
+            if self.peek() == Some(close)
+            {
+                self.next();
+                return Ok(res);
+            }
+            res.push(read(self)?);
+            self.skip_whitespace_and_comments();
+            match self.next()
+            {
+                Some(',') => {},
+                Some(ch) if ch == close => return Ok(res),
+                _ => return Err(self.error(&format!("expected `,` or `{}`", close))),
+            }
+        }
+    }
+
+    fn read_value(&mut self) -> Result<ImpValue, String>
+    {
+        self.skip_whitespace_and_comments();
+        match self.peek()
+        {
+            Some('[') => {
+                self.next();
+                Ok(ImpValue::List(self.read_elements(']', |p| p.read_value())?))
+            },
+            Some('{') => {
+                self.next();
+                Ok(ImpValue::Map(self.read_elements('}', |p| {
+                    let key = p.read_scalar()?;
+                    p.skip_whitespace_and_comments();
+                    if p.next() != Some(':')
+                    {
+                        return Err(p.error(&format!("expected `:` after `{}`", key)));
+                    }
+                    Ok((key, p.read_value()?))
+                })?))
+            },
+            _ => Ok(ImpValue::Scalar(self.read_scalar()?)),
+        }
+    }
+}
+
+/// Read the name and the visibility of a header or a symbol from a mapping, e.g. `"<vector>", public`
+fn read_mapping_pair(values: &[ImpValue]) -> Result<(&str, bool), String>
+{
+    match values
+    {
+        [ImpValue::Scalar(name), ImpValue::Scalar(visibility)] => match visibility.as_str()
+        {
+            "private" => Ok((name, true)),
+            "public" => Ok((name, false)),
+            _ => Err(format!("unknown visibility `{}` of `{}`", visibility, name)),
+        },
+        _ => Err("a mapping must be a list of a name, a visibility, a header and a visibility".into()),
+    }
+}
+
+/// Parse the content of an include-what-you-use mapping file (`.imp`)
+///
+/// Mappings of regular expressions, names starting with `@`, are not supported and skipped.
+pub fn parse_mappings(content: &str) -> Result<Vec<Mapping>, String>
+{
+    let mut parser = ImpParser { chars: content.chars().collect(), pos: 0, line: 1 };
+    let entries = match parser.read_value()?
+    {
+        ImpValue::List(entries) => entries,
+        _ => return Err("a mapping file must be a list".into()),
+    };
+    parser.skip_whitespace_and_comments();
+    if parser.peek().is_some()
+    {
+        return Err(parser.error("unexpected content after the list"));
+    }
+
+    let mut res = Vec::<Mapping>::new();
+    for entry in entries
+    {
+        let fields = match entry
+        {
+            ImpValue::Map(fields) => fields,
+            _ => return Err("an entry of a mapping file must be a map".into()),
+        };
+        for (key, value) in fields
+        {
+            match (key.as_str(), value)
+            {
+                ("ref", ImpValue::Scalar(file)) => res.push(Mapping::Ref(file)),
+                ("include" | "symbol", ImpValue::List(values)) if values.len() == 4 => {
+                    let (name, private) = read_mapping_pair(&values[..2])?;
+                    let (public, _) = read_mapping_pair(&values[2..])?;
+                    if name.starts_with('@')
+                    {
+                        continue;
+                    }
+                    res.push(if key == "include"
+                    {
+                        Mapping::Include { header: name.into(), private, public: public.into() }
+                    }
+                    else
+                    {
+                        Mapping::Symbol { symbol: name.into(), public: public.into() }
+                    });
+                },
+                ("ref" | "include" | "symbol", _) => return Err(format!("invalid `{}` mapping", key)),
+                _ => return Err(format!("unknown mapping `{}`", key)),
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Read a mapping file, with the mappings of the files it refers to in place of the references
+pub fn load_mappings(path: &Path) -> Result<Vec<Mapping>, String>
+{
+    let mut res = Vec::<Mapping>::new();
+    let mut pending = vec![path.to_path_buf()];
+    let mut loaded = Vec::new();
+    while let Some(path) = pending.pop()
+    {
+        if loaded.contains(&path)
+        {
+            continue;
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        for mapping in parse_mappings(&content).map_err(|e| format!("{}: {}", path.display(), e))?
+        {
+            match mapping
+            {
+                Mapping::Ref(file) => pending.push(path.parent().unwrap_or(Path::new("")).join(file)),
+                _ => res.push(mapping),
+            }
+        }
+        loaded.push(path);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_pragmas()
+    {
+        let input = "
+            // IWYU pragma: private, include \"mylib/api.h\"
+            #include <vector> // IWYU pragma: keep
+            #include <map>
+            #include \"config.h\" /* IWYU pragma: export */
+            // IWYU pragma: begin_exports
+            #include <string>
+            #include <memory>
+            // IWYU pragma: end_exports
+            #include <set>
+        ";
+        let pragmas = parse_pragmas(input);
+        assert_eq!(pragmas, Pragmas {
+            kept: vec!["<vector>".into()],
+            exported: vec!["\"config.h\"".into(), "<string>".into(), "<memory>".into()],
+            is_private: true,
+            public_header: Some("\"mylib/api.h\"".into()),
+        });
+        assert!(pragmas.keeps("<string>"));
+        assert!(!pragmas.keeps("<map>"));
+
+        let private = parse_pragmas("// IWYU pragma: private\nconst char* s = \"IWYU pragma: keep\";");
+        assert!(private.is_private);
+        assert_eq!(private.public_header, None);
+    }
+
+    #[test]
+    fn test_parse_mappings()
+    {
+        let input = r#"
+            # Mappings of the project
+            [
+              { include: ["<bits/vector.h>", private, "<vector>", public ] },
+              { include: ['"detail/a.h"', public, "\"a.h\"", public] },
+              { include: ["@<bits/.*>", private, "<all.h>", public] },
+              { symbol: [std::size_t, private, "<cstddef>", public] },
+              { ref: "more.imp" }
+            ]
+        "#;
+        assert_eq!(parse_mappings(input), Ok(vec![
+            Mapping::Include { header: "<bits/vector.h>".into(), private: true, public: "<vector>".into() },
+            Mapping::Include { header: "\"detail/a.h\"".into(), private: false, public: "\"a.h\"".into() },
+            Mapping::Symbol { symbol: "std::size_t".into(), public: "<cstddef>".into() },
+            Mapping::Ref("more.imp".into()),
+        ]));
+
+        assert_eq!(parse_mappings("{ include: [] }"), Err("a mapping file must be a list".into()));
+        assert_eq!(parse_mappings("[ { header: x } ]"), Err("unknown mapping `header`".into()));
+        assert_eq!(parse_mappings("[ { include: [a, secret, b, public] } ]"), Err("unknown visibility `secret` of `a`".into()));
+        assert_eq!(parse_mappings("[\n{ ref: \"a.imp\" }\n"), Err("line 3: expected `,` or `]`".into()));
+    }
+}
@@ -0,0 +1,15 @@
+pub mod comment_remover;
+pub mod string_remover;
+pub mod statement_tree;
+pub mod tokenizer;
+pub mod preprocessor;
+pub mod declaration_finder;
+pub mod member_pairing;
+pub mod linkage_checks;
+pub mod standard_headers;
+pub mod header_db;
+pub mod include_fixer;
+pub mod iwyu;
+pub mod diff;
+pub mod toml;
+pub mod config;
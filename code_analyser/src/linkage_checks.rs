@@ -0,0 +1,172 @@
+use crate::declaration_finder::Declaration;
+use crate::declaration_finder::DeclarationKind;
+use crate::declaration_finder::Dialect;
+use crate::declaration_finder::LanguageLinkage;
+use crate::declaration_finder::Linkage;
+use crate::declaration_finder::Specifier;
+
+/// A function or a variable with C language linkage that uses a C++ type
+#[derive(Debug, PartialEq)]
+pub struct CLinkageLeak
+{
+    pub name: String,
+    // The offending type as written, e.g. `std::string&`
+    pub type_name: String,
+}
+
+/// Find functions defined in source files with external linkage, but not declared in any header
+///
+/// They are only used in the file defining them, so they should be `static` or in an anonymous namespace.
+/// Member functions, explicit specialisations, exported functions and `main` are not reported, and
+/// neither are functions marked `extern` or `inline`, or with C language linkage in C++ sources,
+/// as they are meant to be used elsewhere.
+pub fn find_functions_missing_internal_linkage(sources: &[Declaration], headers: &[Declaration], dialect: Dialect) -> Vec<String>
+{
+    let mut res = Vec::<String>::new();
+    for declaration in sources
+    {
+        let is_meant_for_elsewhere = declaration.specifiers.contains(&Specifier::Extern)
+            || declaration.specifiers.contains(&Specifier::Inline)
+            || (dialect == Dialect::Cpp && declaration.language_linkage == Some(LanguageLinkage::C));
+        if declaration.kind == DeclarationKind::Function
+            && !is_meant_for_elsewhere
+            && declaration.is_definition
+            && declaration.linkage == Linkage::External
+            && declaration.class_name.is_none()
+            && declaration.specialization.is_none()
+            && !declaration.is_exported
+            && declaration.name != "main"
+            && !headers.iter().any(|d| d.name == declaration.name)
+            && !res.contains(&declaration.name)
+        {
+            res.push(declaration.name.clone());
+        }
+    }
+    res
+}
+
+/// Collect names of classes that can't be used from C: declared with `class`, or having member functions
+fn collect_cpp_classes(declarations: &[Declaration], classes: &mut Vec<String>)
+{
+    for declaration in declarations
+    {
+        let has_member_functions = declaration.members.iter().any(|m| m.kind == DeclarationKind::Function);
+        if declaration.kind == DeclarationKind::Class
+            || (matches!(declaration.kind, DeclarationKind::Struct | DeclarationKind::Union) && has_member_functions)
+        {
+            classes.push(declaration.name.clone());
+        }
+        collect_cpp_classes(&declaration.members, classes);
+    }
+}
+
+/// Check if a type can't be used from C, e.g. a reference, a template, a name in a namespace or a C++ class
+fn is_cpp_type(type_name: &str, cpp_classes: &[String]) -> bool
+{
+    type_name.contains(['&', '<', ':'])
+        || type_name
+            .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+            .any(|word| cpp_classes.iter().any(|class| class == word))
+}
+
+/// Find functions and variables with C language linkage that use C++ types in their signatures,
+/// e.g. `void f(std::string& s);` inside `extern "C" { ... }`
+pub fn find_c_linkage_leaks(declarations: &[Declaration]) -> Vec<CLinkageLeak>
+{
+    let mut cpp_classes = Vec::new();
+    collect_cpp_classes(declarations, &mut cpp_classes);
+
+    let mut res = Vec::new();
+    for declaration in declarations
+    {
+        if declaration.language_linkage != Some(LanguageLinkage::C)
+        {
+            continue;
+        }
+
+        let mut types = Vec::<&str>::new();
+        if let Some(signature) = &declaration.signature
+        {
+            types.push(&signature.return_type);
+            types.extend(signature.parameters.iter().map(|p| p.type_name.as_str()));
+        }
+        if let Some(type_name) = &declaration.type_name
+        {
+            types.push(type_name);
+        }
+
+        for type_name in types
+        {
+            if is_cpp_type(type_name, &cpp_classes)
+            {
+                res.push(CLinkageLeak { name: declaration.name.clone(), type_name: type_name.into() });
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Config;
+    use crate::declaration_finder::{find_declarations_detailed, find_declarations_with_config};
+
+    #[test]
+    fn test_missing_internal_linkage()
+    {
+        let header = "
+            void api();
+            namespace ns { void ns_api(); }
+        ";
+        let source = "
+            void api() {}
+            void ns::ns_api() {}
+            namespace ns { void ns_api() {} }
+            void helper() {}
+            static void internal_helper() {}
+            namespace { void hidden_helper() {} }
+            void declared_only();
+            struct Foo { void bar() {} };
+            void Foo::baz() {}
+            template <> void g<int>() {}
+            int main() {}
+            extern void extern_function() {}
+            extern \"C\" void c_function() {}
+            extern \"C\" { void c_block_function() {} }
+            inline void inline_function() {}
+        ";
+        let headers = find_declarations_detailed(header);
+        let sources = find_declarations_detailed(source);
+        assert_eq!(find_functions_missing_internal_linkage(&sources, &headers, Dialect::Cpp), vec!["helper"]);
+
+        let c_source = "void c_helper(void) {}\nstatic void c_internal(void) {}\n";
+        let (c_sources, _) = find_declarations_with_config(c_source, &Config { dialect: Some(Dialect::C), ..Config::default() });
+        assert_eq!(find_functions_missing_internal_linkage(&c_sources, &[], Dialect::C), vec!["c_helper"]);
+    }
+
+    #[test]
+    fn test_c_linkage_leaks()
+    {
+        let input = "
+            class Widget;
+            struct Point { int x, y; };
+            struct Shape { double area() const; };
+            extern \"C\" {
+                int add(int a, int b);
+                void move_point(struct Point* p);
+                void print(const std::string& s);
+                Widget* make_widget();
+                double measure(Shape* shape);
+                extern std::vector<int> values;
+            }
+            void cpp_only(std::string& s);
+        ";
+        assert_eq!(find_c_linkage_leaks(&find_declarations_detailed(input)), vec![
+            CLinkageLeak { name: "print".into(), type_name: "const std::string&".into() },
+            CLinkageLeak { name: "make_widget".into(), type_name: "Widget*".into() },
+            CLinkageLeak { name: "measure".into(), type_name: "Shape*".into() },
+            CLinkageLeak { name: "values".into(), type_name: "std::vector<int>".into() },
+        ]);
+    }
+}
@@ -1,14 +1,20 @@
-//mod bracket_tree;
-mod comment_remover;
-mod string_remover;
-mod statement_tree;
-mod tokenizer;
-mod preprocessor;
-mod declaration_finder;
-mod standard_headers;
+use code_analyser::comment_remover;
+use code_analyser::string_remover;
+use code_analyser::preprocessor;
+use code_analyser::declaration_finder;
+use code_analyser::member_pairing;
+use code_analyser::linkage_checks;
+use code_analyser::standard_headers;
+use code_analyser::include_fixer;
+use code_analyser::diff;
+use code_analyser::declaration_finder::Dialect;
+use code_analyser::config::Config;
+use code_analyser::header_db::{HeaderDb, Standard};
+use code_analyser::iwyu;
 
 use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::io::{self, Read};
 
@@ -21,7 +27,24 @@ enum Task {
     PrintCustomIncludes,
     PrintIncludesWithBrackets,
     PrintDeclarations,
+    PrintPrototypes,
+    PrintDeprecated,
+    PrintUnpairedMembers,
+    PrintMissingInternalLinkage,
+    PrintCLinkageLeaks,
     PrintUnusedStandardHeaders,
+    PrintMissingStandardHeaders,
+    PrintStandardVersionIssues,
+    Fix,
+}
+
+/// What --fix does with the fixed files
+#[derive(PartialEq)]
+enum FixMode {
+    // Print a unified diff without modifying files
+    Diff,
+    // Write the fixed files
+    InPlace,
 }
 
 fn print_help() {
@@ -33,19 +56,62 @@ fn print_help() {
     println!("    --remove-comments-and-strings <filename>");
     println!("        Remove comments, strings and chars from a single C/C++/header file, prints output to the standard output");
     println!("    --print-includes <filename>");
-    println!("        Print headers used in #include directives");
+    println!("        Print headers used in #include directives and modules used in import declarations");
     println!("    --print-standard-includes <filename>");
-    println!("        Print standard headers used in #include directives (headers inside <>)");
+    println!("        Print standard headers used in #include directives and imported as header units (headers inside <>)");
     println!("    --print-custom-includes <filename>");
-    println!("        Print custom headers used in #include directives (headers inside \"\")");
+    println!("        Print custom headers used in #include directives and imported as header units (headers inside \"\")");
     println!("    --print-includes-with-brackets <filename>");
-    println!("        Print headers used in #include directives. Preserve <> and \"\"");
+    println!("        Print headers used in #include directives and modules used in import declarations. Preserve <> and \"\"");
     println!("    --find-declarations <filename>");
-    println!("        Print all declarations and definitions");
+    println!("        Print all declarations and definitions, including class members, module declarations and imports");
+    println!("    --print-prototypes <filename>");
+    println!("        Print normalised prototypes of all functions, including member functions");
+    println!("    --print-deprecated <filename>");
+    println!("        Print declarations marked as deprecated with any attribute syntax, including class members");
+    println!("    --print-unpaired-members <filename>...");
+    println!("        Print class members declared but not defined, and defined but not declared");
+    println!("    --print-missing-internal-linkage <filename>...");
+    println!("        Print functions defined in source files with external linkage, but not declared in any of the given headers");
+    println!("    --print-c-linkage-leaks <filename>...");
+    println!("        Print functions and variables with C language linkage that use C++ types");
     println!("    --print-unused-standard-headers");
     println!("        Print unused standards headers (headers inside <>)");
-    println!("");
-    println!("To skip identifiers, export them to SKIP_IDENTIFIERS, e.g. export SKIP_IDENTIFIERS=identifier1,identifier2,identifier3");
+    println!("    --print-missing-standard-headers <filename>...");
+    println!("        Print symbols of the standard library used without including a header declaring them, and the headers to include");
+    println!("    --print-standard-version-issues <filename>...");
+    println!("        Print standard headers and symbols introduced after, removed in or deprecated in the standard given with --std");
+    println!("    --fix <filename>...");
+    println!("        Remove unused standard headers, including header units, and include missing ones, unless `std` is imported. Prints the changes as a unified diff unless --in-place is given");
+    println!();
+    println!("Options:");
+    println!("    --dialect=<c|c++>");
+    println!("        Parse files as C or C++. By default `.c` files are C and all other files are C++");
+    println!("    --std=<c++98|c++03|c++11|c++14|c++17|c++20|c++23|c++26>");
+    println!("        C++ standard the files are written in");
+    println!("    --skip-identifiers=<identifier>,...");
+    println!("        Remove identifiers before parsing, e.g. project macros expanding to nothing");
+    println!("    --replace=<identifier>=<replacement>");
+    println!("        Replace an identifier before parsing, e.g. --replace=FINAL=final");
+    println!("    --define=<name>[=<value>]");
+    println!("        Define an object-like macro");
+    println!("    --include-path=<directory>");
+    println!("        Add a directory to look for headers in");
+    println!("    --standard-headers-db=<filename>");
+    println!("        Read symbols of standard headers from a file, in addition to the built-in database. See data/standard_headers.toml");
+    println!("    --header-db=<name|filename>");
+    println!("        Use a database of symbols of other headers, built-in ({}) or read from a file", code_analyser::header_db::BUILTIN_DBS.join(", "));
+    println!("    --mapping-file=<filename>");
+    println!("        Read an include-what-you-use mapping file (.imp) of private and public headers and symbols");
+    println!("    --diff");
+    println!("        Print the changes made by --fix as a unified diff, which is the default");
+    println!("    --in-place");
+    println!("        Write the changes made by --fix to the files");
+    println!("    --config=<filename>");
+    println!("        Read the configuration from a file instead of looking for {}", code_analyser::config::CONFIG_FILE_NAME);
+    println!();
+    println!("The configuration is read from {} in the directory of the input file or its parent directories.", code_analyser::config::CONFIG_FILE_NAME);
+    println!("Options given on the command line take precedence over it.");
 }
 
 fn read_file_content(path: &str) -> String
@@ -56,7 +122,7 @@ fn read_file_content(path: &str) -> String
         return res;
     }
 
-    return fs::read_to_string(path).unwrap();
+    fs::read_to_string(path).unwrap()
 }
 
 fn read_single_file_content(file_names: Vec::<String>) -> String
@@ -69,9 +135,173 @@ fn read_single_file_content(file_names: Vec::<String>) -> String
     read_file_content(file_names[0].as_str())
 }
 
+/// Get the configuration of a file, with `overrides` from the command line applied
+///
+/// The configuration file is found next to the file unless given explicitly,
+/// and the dialect is detected from the file name if not configured.
+fn load_config(file_name: &str, config_file: Option<&Path>, overrides: &Config) -> Config
+{
+    let config = match config_file
+    {
+        Some(path) => Config::load(path).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => Config::for_file(file_name),
+    };
+    let mut config = config.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
+    config.merge(overrides.clone());
+    if config.dialect.is_none()
+    {
+        config.dialect = Some(Dialect::from_file_name(file_name));
+    }
+    config
+}
+
+/// Find declarations, printing problems found while parsing to the standard error
+fn find_declarations(file_name: &str, file_content: &str, config: &Config) -> Vec<declaration_finder::Declaration>
+{
+    let (declarations, diagnostics) = declaration_finder::find_declarations_with_config(file_content, config);
+    for diagnostic in diagnostics
+    {
+        eprintln!("warning: {}: {}", file_name, diagnostic);
+    }
+    declarations
+}
+
+/// Get the database of the standard headers and of the configured other headers, with headers from
+/// the configured standard headers file replacing the built-in ones
+fn load_standard_headers_db(config: &Config) -> HeaderDb
+{
+    let mut db = HeaderDb::standard();
+    let mut files = Vec::<PathBuf>::new();
+    for name in &config.header_dbs
+    {
+        match HeaderDb::builtin(name)
+        {
+            Some(builtin) => db.merge(builtin),
+            None => files.push(PathBuf::from(name)),
+        }
+    }
+    files.extend(config.standard_headers_db.clone());
+
+    for path in files
+    {
+        let res = HeaderDb::load(&path).and_then(|custom| {
+            db.merge(custom);
+            db.validate().map_err(|e| format!("{}: {}", path.display(), e))
+        });
+        if let Err(e) = res
+        {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+
+    for path in &config.mapping_files
+    {
+        match iwyu::load_mappings(path)
+        {
+            Ok(mappings) => mappings.iter().for_each(|m| db.add_mapping(m)),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            },
+        }
+    }
+    db
+}
+
+/// Find the file of a header included by a file, in brackets, in the configured include paths and,
+/// for headers in `""`, the directory of the file
+fn find_included_file(file_name: &str, header: &str, config: &Config) -> Option<PathBuf>
+{
+    let name = &header[1..header.len() - 1];
+    let own_dir = Path::new(file_name).parent().filter(|_| header.starts_with('"') && file_name != "-");
+    own_dir
+        .into_iter()
+        .chain(config.include_paths.iter().map(PathBuf::as_path))
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Apply the IWYU pragmas of the files a file includes, found with find_included_file
+///
+/// Private headers with a public header to include instead are added to `db` and the standard
+/// headers the included files export are returned.
+fn apply_included_pragmas(file_name: &str, file_content: &str, config: &Config, db: &mut HeaderDb) -> Vec<String>
+{
+    let mut exported = Vec::<String>::new();
+    // Modules imported by name have no brackets
+    for header in preprocessor::get_includes_with_brackets(file_content).into_iter().filter(|h| h.starts_with(['<', '"']))
+    {
+        let content = match find_included_file(file_name, &header, config).and_then(|path| fs::read_to_string(path).ok())
+        {
+            Some(content) => content,
+            None => continue,
+        };
+        let pragmas = iwyu::parse_pragmas(&content);
+        if pragmas.is_private && let Some(public) = pragmas.public_header
+        {
+            db.add_mapping(&iwyu::Mapping::Include { header: header.clone(), private: true, public });
+        }
+        exported.extend(pragmas.exported.iter().filter_map(|h| h.strip_prefix('<')?.strip_suffix('>')).map(String::from));
+    }
+    exported
+}
+
+/// Get headers a check is configured to ignore, e.g. `[checks.unused-standard-headers] ignore = ["cassert"]`
+fn ignored_headers(config: &Config, check: &str) -> Vec<String>
+{
+    config.check_option(check, "ignore").and_then(|value| value.as_string_array()).unwrap_or_default()
+}
+
+/// Check if a file is a header by its extension
+fn is_header(file_name: &str) -> bool
+{
+    let extension = Path::new(file_name).extension().and_then(|e| e.to_str()).unwrap_or_default();
+    matches!(extension, "h" | "hh" | "hpp" | "hxx" | "h++" | "inl")
+}
+
+fn print_declarations(declarations: &[declaration_finder::Declaration])
+{
+    for declaration in declarations
+    {
+        println!("{}", declaration);
+        print_declarations(&declaration.members);
+    }
+}
+
+fn print_prototypes(declarations: &[declaration_finder::Declaration])
+{
+    for declaration in declarations
+    {
+        if let Some(prototype) = declaration.prototype()
+        {
+            println!("{}", prototype);
+        }
+        print_prototypes(&declaration.members);
+    }
+}
+
+fn print_deprecated(declarations: &[declaration_finder::Declaration])
+{
+    for declaration in declarations
+    {
+        if declaration.has_attribute("deprecated")
+        {
+            println!("{}", declaration);
+        }
+        print_deprecated(&declaration.members);
+    }
+}
+
 fn main() {
     let mut file_names = Vec::new();
     let mut task = Task::PrintHelp;
+    let mut overrides = Config::default();
+    let mut config_file = None;
+    let mut fix_mode = FixMode::Diff;
 
     for arg in env::args().skip(1) {
         if arg.starts_with("--") {
@@ -84,7 +314,51 @@ fn main() {
                 "--print-custom-includes" => task = Task::PrintCustomIncludes,
                 "--print-includes-with-brackets" => task = Task::PrintIncludesWithBrackets,
                 "--find-declarations" => task = Task::PrintDeclarations,
+                "--print-prototypes" => task = Task::PrintPrototypes,
+                "--print-deprecated" => task = Task::PrintDeprecated,
+                "--print-unpaired-members" => task = Task::PrintUnpairedMembers,
+                "--print-missing-internal-linkage" => task = Task::PrintMissingInternalLinkage,
+                "--print-c-linkage-leaks" => task = Task::PrintCLinkageLeaks,
                 "--print-unused-standard-headers" => task = Task::PrintUnusedStandardHeaders,
+                "--print-missing-standard-headers" => task = Task::PrintMissingStandardHeaders,
+                "--print-standard-version-issues" => task = Task::PrintStandardVersionIssues,
+                "--fix" => task = Task::Fix,
+                "--diff" => fix_mode = FixMode::Diff,
+                "--in-place" => fix_mode = FixMode::InPlace,
+                _ if arg.starts_with("--dialect=") => {
+                    overrides.dialect = Dialect::from_name(&arg["--dialect=".len()..]);
+                    if overrides.dialect.is_none()
+                    {
+                        print_help();
+                        process::exit(1);
+                    }
+                },
+                _ if arg.starts_with("--std=") => {
+                    overrides.standard = Standard::from_name(&arg["--std=".len()..]);
+                    if overrides.standard.is_none()
+                    {
+                        print_help();
+                        process::exit(1);
+                    }
+                },
+                _ if arg.starts_with("--skip-identifiers=") => {
+                    overrides.skip_identifiers.extend(arg["--skip-identifiers=".len()..].split(',').map(String::from));
+                },
+                _ if arg.starts_with("--replace=") => {
+                    let (identifier, replacement) = arg["--replace=".len()..].split_once('=').unwrap_or_else(|| {
+                        print_help();
+                        process::exit(1);
+                    });
+                    overrides.replacements.insert(identifier.into(), replacement.into());
+                },
+                _ if arg.starts_with("--define=") => overrides.add_define(&arg["--define=".len()..]),
+                _ if arg.starts_with("--include-path=") => overrides.include_paths.push(PathBuf::from(&arg["--include-path=".len()..])),
+                _ if arg.starts_with("--standard-headers-db=") => {
+                    overrides.standard_headers_db = Some(PathBuf::from(&arg["--standard-headers-db=".len()..]));
+                },
+                _ if arg.starts_with("--header-db=") => overrides.header_dbs.push(arg["--header-db=".len()..].into()),
+                _ if arg.starts_with("--mapping-file=") => overrides.mapping_files.push(PathBuf::from(&arg["--mapping-file=".len()..])),
+                _ if arg.starts_with("--config=") => config_file = Some(PathBuf::from(&arg["--config=".len()..])),
                 _ => {
                     print_help();
                     process::exit(1);
@@ -134,19 +408,198 @@ fn main() {
             }
         },
         Task::PrintDeclarations => {
+            let file_name = file_names.first().cloned().unwrap_or_default();
             let file_content = read_single_file_content(file_names);
-            for header in declaration_finder::find_declarations(&file_content).into_iter() {
-                println!("{}", header);
+            let config = load_config(&file_name, config_file.as_deref(), &overrides);
+            print_declarations(&find_declarations(&file_name, &file_content, &config));
+        },
+        Task::PrintPrototypes => {
+            let file_name = file_names.first().cloned().unwrap_or_default();
+            let file_content = read_single_file_content(file_names);
+            let config = load_config(&file_name, config_file.as_deref(), &overrides);
+            print_prototypes(&find_declarations(&file_name, &file_content, &config));
+        },
+        Task::PrintDeprecated => {
+            let file_name = file_names.first().cloned().unwrap_or_default();
+            let file_content = read_single_file_content(file_names);
+            let config = load_config(&file_name, config_file.as_deref(), &overrides);
+            print_deprecated(&find_declarations(&file_name, &file_content, &config));
+        },
+        Task::PrintUnpairedMembers => {
+            let mut declarations = Vec::new();
+            for file in file_names
+            {
+                let config = load_config(&file, config_file.as_deref(), &overrides);
+                declarations.extend(find_declarations(&file, &read_file_content(&file), &config));
+            }
+
+            let unpaired = member_pairing::find_unpaired_members(&declarations);
+            for name in unpaired.declared_only
+            {
+                println!("declared but not defined : {}", name);
+            }
+            for name in unpaired.defined_only
+            {
+                println!("defined but not declared : {}", name);
+            }
+        },
+        Task::PrintMissingInternalLinkage => {
+            let mut headers = Vec::new();
+            let mut sources = Vec::new();
+            for file in &file_names
+            {
+                let config = load_config(file, config_file.as_deref(), &overrides);
+                let declarations = find_declarations(file, &read_file_content(file), &config);
+                if is_header(file)
+                {
+                    headers.extend(declarations);
+                }
+                else
+                {
+                    sources.push((file, declarations, config.dialect.unwrap_or(Dialect::Cpp)));
+                }
+            }
+
+            for (file, declarations, dialect) in sources
+            {
+                for name in linkage_checks::find_functions_missing_internal_linkage(&declarations, &headers, dialect)
+                {
+                    println!("{} : {}", file, name);
+                }
+            }
+        },
+        Task::PrintCLinkageLeaks => {
+            for file in &file_names
+            {
+                let config = load_config(file, config_file.as_deref(), &overrides);
+                let declarations = find_declarations(file, &read_file_content(file), &config);
+                for leak in linkage_checks::find_c_linkage_leaks(&declarations)
+                {
+                    println!("{} : {} : {}", file, leak.name, leak.type_name);
+                }
             }
         },
         Task::PrintUnusedStandardHeaders => {
             for file in file_names
             {
-                for header in standard_headers::get_unused_headers(&read_file_content(&file))
+                let config = load_config(&file, config_file.as_deref(), &overrides);
+                let ignored = ignored_headers(&config, "unused-standard-headers");
+                let mut db = load_standard_headers_db(&config);
+                let file_content = read_file_content(&file);
+                apply_included_pragmas(&file, &file_content, &config, &mut db);
+                for header in standard_headers::get_unused_headers_with_db(&file_content, &db)
                 {
+                    if ignored.contains(&header)
+                    {
+                        continue;
+                    }
                     println!("{} : {}", file, header);
                 }
             }
-        }
+        },
+        Task::PrintMissingStandardHeaders => {
+            for file in file_names
+            {
+                let config = load_config(&file, config_file.as_deref(), &overrides);
+                let ignored = ignored_headers(&config, "missing-standard-headers");
+                let mut db = load_standard_headers_db(&config);
+                let dialect = config.dialect.unwrap_or(Dialect::Cpp);
+                let file_content = read_file_content(&file);
+                let exported = apply_included_pragmas(&file, &file_content, &config, &mut db);
+                for missing in standard_headers::get_missing_headers_with_db(&file_content, &db, dialect, &exported)
+                {
+                    if missing.headers.iter().any(|h| ignored.contains(h))
+                    {
+                        continue;
+                    }
+                    let includes: Vec<String> = missing.headers.iter().map(|h| format!("#include <{}>", h)).collect();
+                    println!("{} : {} : {}", file, missing.symbol, includes.join(" or "));
+                }
+            }
+        },
+        Task::PrintStandardVersionIssues => {
+            for file in file_names
+            {
+                let config = load_config(&file, config_file.as_deref(), &overrides);
+                let standard = config.standard.unwrap_or_else(|| {
+                    eprintln!("error: {}: no C++ standard given, use --std=<standard> or `std` in {}", file, code_analyser::config::CONFIG_FILE_NAME);
+                    process::exit(1);
+                });
+                let db = load_standard_headers_db(&config);
+                for issue in standard_headers::get_version_issues_with_db(&read_file_content(&file), &db, standard)
+                {
+                    println!("{} : {} : {}", file, issue.name, issue.problem);
+                }
+            }
+        },
+        Task::Fix => {
+            for file in file_names
+            {
+                let config = load_config(&file, config_file.as_deref(), &overrides);
+                let mut db = load_standard_headers_db(&config);
+                let dialect = config.dialect.unwrap_or(Dialect::Cpp);
+                let file_content = read_file_content(&file);
+                let exported = apply_included_pragmas(&file, &file_content, &config, &mut db);
+
+                let ignored_unused = ignored_headers(&config, "unused-standard-headers");
+                let unused: Vec<String> = standard_headers::get_unused_headers_with_db(&file_content, &db)
+                    .into_iter()
+                    .filter(|h| !ignored_unused.contains(h))
+                    .collect();
+
+                // Symbols which may be names of the file itself and symbols declared by several headers are
+                // left to the user, as any of the headers may be right
+                let ignored_missing = ignored_headers(&config, "missing-standard-headers");
+                let mut missing = Vec::<String>::new();
+                for m in standard_headers::get_missing_headers_with_db(&file_content, &db, dialect, &exported)
+                {
+                    if m.headers.iter().any(|h| ignored_missing.contains(h))
+                    {
+                        continue;
+                    }
+                    if !m.is_reliable
+                    {
+                        eprintln!("warning: {}: `{}` is neither qualified with `std::` nor called, not fixed: {}", file, m.symbol, m.headers.join(", "));
+                    }
+                    else if m.headers.len() > 1
+                    {
+                        eprintln!("warning: {}: `{}` is declared by several headers, not fixed: {}", file, m.symbol, m.headers.join(", "));
+                    }
+                    else if !missing.contains(&m.headers[0])
+                    {
+                        missing.push(m.headers[0].clone());
+                    }
+                }
+
+                // Includes would go above `import std;`, which provides everything but macros
+                let modules = preprocessor::get_imported_modules(&file_content);
+                if modules.iter().any(|m| m == "std" || m == "std.compat") && !missing.is_empty()
+                {
+                    eprintln!("warning: {}: `std` is imported, missing headers not inserted: {}", file, missing.join(", "));
+                    missing.clear();
+                }
+
+                let removed = include_fixer::remove_includes(&file_content, &unused);
+                // E.g. exported header units, `export import <vector>;`, or includes sharing a line with code
+                for header in preprocessor::get_standard_includes(&removed).iter().filter(|h| unused.contains(h))
+                {
+                    eprintln!("warning: {}: <{}> is unused, but not removed", file, header);
+                }
+                let fixed = include_fixer::insert_includes(&removed, &missing);
+                match fix_mode
+                {
+                    FixMode::Diff => print!("{}", diff::unified_diff(&file, &file_content, &fixed)),
+                    // The standard input can't be modified, the fixed content is printed instead
+                    FixMode::InPlace if file == "-" => print!("{}", fixed),
+                    FixMode::InPlace => {
+                        if fixed != file_content && let Err(e) = fs::write(&file, fixed)
+                        {
+                            eprintln!("error: {}: {}", file, e);
+                            process::exit(1);
+                        }
+                    },
+                }
+            }
+        },
     }
 }
@@ -0,0 +1,233 @@
+use crate::declaration_finder::Declaration;
+use crate::declaration_finder::DeclarationKind;
+use crate::declaration_finder::FunctionSignature;
+use crate::declaration_finder::Specifier;
+
+/// Class members whose declaration and definition do not match each other
+#[derive(Debug, PartialEq)]
+pub struct UnpairedMembers
+{
+    // Declared in a class, but not defined anywhere, e.g. `void bar();` without `void Foo::bar() {}`
+    //
+    // Functions are named with their parameter types, e.g. `Foo::bar(int)`.
+    pub declared_only: Vec<String>,
+    // Defined outside of a class, but not declared in it, e.g. `void Foo::baz() {}` without `void baz();`
+    pub defined_only: Vec<String>,
+}
+
+/// Check if an in-class declaration needs a definition outside of the class
+fn needs_definition(member: &Declaration) -> bool
+{
+    if member.is_definition || member.specifiers.contains(&Specifier::Friend)
+    {
+        return false;
+    }
+
+    match member.kind
+    {
+        DeclarationKind::Function => !member.specifiers.contains(&Specifier::PureVirtual),
+        DeclarationKind::Variable => member.specifiers.contains(&Specifier::Static),
+        _ => false,
+    }
+}
+
+/// Type of a parameter without a top-level `const`, which is not a part of the function type,
+/// e.g. `int` for `const int`, but `const int*` for `const int*`
+fn parameter_type(type_name: &str) -> &str
+{
+    let type_name = type_name.strip_suffix(" const").unwrap_or(type_name);
+    match type_name.strip_prefix("const ")
+    {
+        Some(rest) if !rest.ends_with(['*', '&', ']']) => rest,
+        _ => type_name,
+    }
+}
+
+/// Parameter types and qualifiers telling overloads apart, e.g. `(int) const`
+fn overload_key(signature: &FunctionSignature) -> String
+{
+    let mut types: Vec<&str> = signature.parameters.iter().map(|p| parameter_type(&p.type_name)).collect();
+    if signature.is_variadic
+    {
+        types.push("...");
+    }
+    let mut res = format!("({})", types.join(", "));
+    if signature.is_const
+    {
+        res += " const";
+    }
+    if signature.is_volatile
+    {
+        res += " volatile";
+    }
+    if let Some(ref_qualifier) = &signature.ref_qualifier
+    {
+        res += &format!(" {}", ref_qualifier);
+    }
+    res
+}
+
+/// Name of a member as reported, with the parameter types of a function, e.g. `Foo::bar(int)`
+fn member_name(member: &Declaration) -> String
+{
+    match &member.signature
+    {
+        Some(signature) => format!("{}{}", member.name, overload_key(signature)),
+        None => member.name.clone(),
+    }
+}
+
+/// Check if a declaration and a definition are of the same member, telling overloads apart
+fn is_same_member(a: &Declaration, b: &Declaration) -> bool
+{
+    a.name == b.name && a.signature.as_ref().map(overload_key) == b.signature.as_ref().map(overload_key)
+}
+
+/// Collect classes, structs and unions (including nested ones) with a body
+fn collect_classes<'a>(declarations: &'a [Declaration], classes: &mut Vec<&'a Declaration>)
+{
+    for declaration in declarations
+    {
+        if matches!(declaration.kind, DeclarationKind::Class | DeclarationKind::Struct | DeclarationKind::Union) && declaration.is_definition
+        {
+            classes.push(declaration);
+            collect_classes(&declaration.members, classes);
+        }
+    }
+}
+
+/// Pair in-class member declarations with out-of-class definitions
+///
+/// `declarations` are usually collected from a header and its source files.
+/// Definitions are only checked against classes found in `declarations`,
+/// so `void ns::f() {}` is not reported if `ns` is a namespace.
+pub fn find_unpaired_members(declarations: &[Declaration]) -> UnpairedMembers
+{
+    let mut classes = Vec::new();
+    collect_classes(declarations, &mut classes);
+
+    let definitions: Vec<&Declaration> = declarations
+        .iter()
+        .filter(|d| d.class_name.is_some() && d.is_definition)
+        .collect();
+
+    let mut res = UnpairedMembers {
+        declared_only: Vec::new(),
+        defined_only: Vec::new(),
+    };
+
+    for class in &classes
+    {
+        for member in &class.members
+        {
+            let name = member_name(member);
+            if needs_definition(member)
+                && !definitions.iter().any(|d| is_same_member(d, member))
+                && !res.declared_only.contains(&name)
+            {
+                res.declared_only.push(name);
+            }
+        }
+    }
+
+    for definition in definitions
+    {
+        let class_name = definition.class_name.as_ref().unwrap();
+        let class = match classes.iter().find(|c| c.name == *class_name)
+        {
+            Some(class) => class,
+            None => continue,
+        };
+
+        let name = member_name(definition);
+        if !class.members.iter().any(|m| is_same_member(m, definition)) && !res.defined_only.contains(&name)
+        {
+            res.defined_only.push(name);
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::declaration_finder::find_declarations_detailed;
+
+    #[test]
+    fn test_all_paired()
+    {
+        let header = "
+            namespace ns {
+            class Foo {
+            public:
+                Foo();
+                ~Foo();
+                void bar();
+                void inline_function() {}
+                virtual void pure() = 0;
+                Foo(const Foo&) = delete;
+                static int counter;
+                int value;
+            };
+            }
+        ";
+        let source = "
+            namespace ns {
+            Foo::Foo() : value(0) {}
+            Foo::~Foo() {}
+            void Foo::bar() {}
+            int Foo::counter = 0;
+            }
+        ";
+        let mut declarations = find_declarations_detailed(header);
+        declarations.extend(find_declarations_detailed(source));
+        assert_eq!(find_unpaired_members(&declarations), UnpairedMembers {
+            declared_only: vec![],
+            defined_only: vec![],
+        });
+    }
+
+    #[test]
+    fn test_unpaired()
+    {
+        let input = "
+            struct Foo {
+                void declared();
+                static int declared_counter;
+                struct Nested {
+                    void nested();
+                };
+            };
+            void Foo::defined() {}
+            int Foo::defined_counter = 0;
+            void ns::free_function() {}
+        ";
+        assert_eq!(find_unpaired_members(&find_declarations_detailed(input)), UnpairedMembers {
+            declared_only: vec!["Foo::declared()".into(), "Foo::declared_counter".into(), "Foo::Nested::nested()".into()],
+            defined_only: vec!["Foo::defined()".into(), "Foo::defined_counter".into()],
+        });
+    }
+
+    #[test]
+    fn test_overloads()
+    {
+        let input = "
+            struct Foo {
+                void f(int);
+                void f(double);
+                void g(const std::string& s) const;
+                void g(const std::string& s);
+                void h(const int x);
+            };
+            void Foo::f(int x) {}
+            void Foo::g(const std::string& s) const {}
+            void Foo::g(std::string s) {}
+            void Foo::h(int x) {}
+        ";
+        assert_eq!(find_unpaired_members(&find_declarations_detailed(input)), UnpairedMembers {
+            declared_only: vec!["Foo::f(double)".into(), "Foo::g(const std::string&)".into()],
+            defined_only: vec!["Foo::g(std::string)".into()],
+        });
+    }
+}
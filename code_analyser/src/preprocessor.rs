@@ -2,12 +2,17 @@ use crate::tokenizer::tokenize;
 use crate::tokenizer::Token;
 
 #[derive(PartialEq)]
+#[allow(clippy::enum_variant_names)]
 enum State
 {
     LookingForOctothorp,
     LookingForDefineWord,
     LookingForIdentifier,
     LookingForNewLine,
+    // Inside a directive other than #include
+    SkippingDirective,
+    // Inside `import ...;`
+    LookingForSemicolon,
 }
 
 pub fn get_preprocessor_definitions(file_content: &str) -> Vec<String>
@@ -20,12 +25,9 @@ pub fn get_preprocessor_definitions(file_content: &str) -> Vec<String>
     {
         if state == State::LookingForOctothorp
         {
-            if let Token::Operator(s) = token
+            if let Token::Operator(s) = token && s == "#"
             {
-                if s == "#".to_string()
-                {
-                    state = State::LookingForDefineWord;
-                }
+                state = State::LookingForDefineWord;
             }
 
             continue;
@@ -35,7 +37,7 @@ pub fn get_preprocessor_definitions(file_content: &str) -> Vec<String>
         {
             if let Token::Identifier(s) = token
             {
-                if s == "define".to_string()
+                if s == "define"
                 {
                     state = State::LookingForIdentifier;
                     continue;
@@ -74,23 +76,41 @@ pub fn get_preprocessor_definitions(file_content: &str) -> Vec<String>
     res
 }
 
+/// Check if an imported name is a module or a header unit, e.g. `std`, `:part`, `<vector>` or `"a.h"`
+fn is_import_name(name: &str) -> bool
+{
+    match name.chars().next()
+    {
+        Some(c) => c == '<' || c == '"' || c == ':' || c == '_' || c.is_alphabetic(),
+        None => false,
+    }
+}
+
+/// Headers used in #include directives and C++20 imports, in the order of appearance
+///
+/// Headers keep their brackets, e.g. `<vector>` or `"a.h"` (including header units, e.g. `import <vector>;`).
+/// Modules are returned without brackets, e.g. `std` for `import std;` or `:part` for `import :part;`.
 pub fn get_includes_with_brackets(file_content: &str) -> Vec<String>
 {
     let mut res = Vec::<String>::new();
     let tokens = tokenize(file_content);
     let mut state = State::LookingForOctothorp;
     let mut new_include = String::new();
+    // `import` is only a keyword at the start of a declaration, optionally after `export`
+    let mut is_declaration_start = true;
 
     for token in tokens.into_iter()
     {
         if state == State::LookingForOctothorp
         {
-            if let Token::Operator(s) = token
+            match token
             {
-                if s == "#".to_string()
-                {
-                    state = State::LookingForDefineWord;
-                }
+                Token::Operator(s) if s == "#" => state = State::LookingForDefineWord,
+                Token::WhiteSpace(_) | Token::NewLine(_) | Token::LineContinuation(_) | Token::Comment(_) => {},
+                Token::Identifier(s) if s == "import" && is_declaration_start => state = State::LookingForSemicolon,
+                Token::Identifier(s) if s == "export" && is_declaration_start => {},
+                Token::Operator(s) => is_declaration_start = s == ";" || s == "{" || s == "}",
+                _ => is_declaration_start = false,
             }
 
             continue;
@@ -100,7 +120,7 @@ pub fn get_includes_with_brackets(file_content: &str) -> Vec<String>
         {
             if let Token::Identifier(s) = token
             {
-                if s == "include".to_string()
+                if s == "include"
                 {
                     state = State::LookingForNewLine;
                     continue;
@@ -111,7 +131,37 @@ pub fn get_includes_with_brackets(file_content: &str) -> Vec<String>
                 continue;
             }
 
-            state = State::LookingForOctothorp;
+            state = State::SkippingDirective;
+            continue;
+        }
+
+        if state == State::SkippingDirective
+        {
+            if let Token::NewLine(_) = token
+            {
+                state = State::LookingForOctothorp;
+            }
+            continue;
+        }
+
+        if state == State::LookingForSemicolon
+        {
+            match token
+            {
+                Token::Operator(s) if s == ";" => {
+                    // Attributes are not a part of the name, e.g. `import std [[deprecated]];`
+                    let name = new_include.split("[[").next().unwrap_or_default();
+                    if is_import_name(name)
+                    {
+                        res.push(name.into());
+                    }
+                    new_include = String::new();
+                    state = State::LookingForOctothorp;
+                    is_declaration_start = true;
+                },
+                Token::String(s) | Token::Operator(s) | Token::Identifier(s) | Token::Number(s) => new_include.push_str(&s),
+                _ => {},
+            }
             continue;
         }
 
@@ -143,7 +193,7 @@ pub fn get_includes_with_brackets(file_content: &str) -> Vec<String>
         }
     }
 
-    if (state == State::LookingForNewLine) && (new_include != "")
+    if (state == State::LookingForNewLine) && !new_include.is_empty()
     {
         res.push(new_include);
     }
@@ -159,13 +209,26 @@ fn remove_first_and_last_char(s: String) -> String
     chars.as_str().to_string()
 }
 
-fn remove_first_and_last_char_from_each(vec: Vec<String>) -> Vec<String>
+/// Remove `<>` or `""` around a header, modules have no brackets
+fn remove_brackets(s: String) -> String
+{
+    if s.starts_with('<') || s.starts_with('"')
+    {
+        remove_first_and_last_char(s)
+    }
+    else
+    {
+        s
+    }
+}
+
+fn remove_brackets_from_each(vec: Vec<String>) -> Vec<String>
 {
     let mut res = Vec::<String>::new();
 
     for s in vec
     {
-        res.push(remove_first_and_last_char(s));
+        res.push(remove_brackets(s));
     }
 
     res
@@ -173,7 +236,7 @@ fn remove_first_and_last_char_from_each(vec: Vec<String>) -> Vec<String>
 
 pub fn get_includes(file_content: &str) -> Vec<String>
 {
-    remove_first_and_last_char_from_each(get_includes_with_brackets(file_content))
+    remove_brackets_from_each(get_includes_with_brackets(file_content))
 }
 
 pub fn get_custom_includes(file_content: &str) -> Vec<String>
@@ -181,7 +244,7 @@ pub fn get_custom_includes(file_content: &str) -> Vec<String>
     get_includes_with_brackets(file_content)
         .into_iter()
         .filter(|s| s.starts_with("\""))
-        .map(|s| remove_first_and_last_char(s))
+        .map(remove_first_and_last_char)
         .collect::<Vec<String>>()
 }
 
@@ -190,10 +253,97 @@ pub fn get_standard_includes(file_content: &str) -> Vec<String>
     get_includes_with_brackets(file_content)
         .into_iter()
         .filter(|s| s.starts_with("<"))
-        .map(|s| remove_first_and_last_char(s))
+        .map(remove_first_and_last_char)
         .collect::<Vec<String>>()
 }
 
+/// Modules imported by name, e.g. `std` for `import std;`, but not header units like `import <vector>;`
+pub fn get_imported_modules(file_content: &str) -> Vec<String>
+{
+    get_includes_with_brackets(file_content)
+        .into_iter()
+        .filter(|s| !s.starts_with("<") && !s.starts_with("\""))
+        .collect::<Vec<String>>()
+}
+
+/// Get the bodies of `#define` directives and the conditions of `#if`, `#elif`, `#ifdef` and the like,
+/// one per directive, e.g. `assert(x)` for `#define CHECK(x) assert(x)`
+///
+/// Names and parameters of the defined macros are not part of the bodies. Other directives, like
+/// `#include` or `#undef`, are skipped.
+pub fn get_directive_expressions(file_content: &str) -> Vec<String>
+{
+    let mut res = Vec::<String>::new();
+    let mut state = State::LookingForOctothorp;
+    let mut is_define = false;
+    // Tokens of the directive after its name
+    let mut tokens = Vec::<Token>::new();
+
+    for token in tokenize(file_content).into_iter().chain([Token::NewLine("\n".into())])
+    {
+        // Single-line comments end with the new line
+        let is_new_line = match &token
+        {
+            Token::NewLine(_) => true,
+            Token::Comment(s) => s.ends_with(['\n', '\r']),
+            _ => false,
+        };
+        if is_new_line
+        {
+            if state == State::LookingForNewLine
+            {
+                res.push(directive_body(&tokens, is_define));
+            }
+            state = State::LookingForOctothorp;
+            continue;
+        }
+
+        match state
+        {
+            State::LookingForOctothorp if token == Token::Operator("#".into()) => {
+                state = State::LookingForDefineWord;
+                tokens.clear();
+            },
+            State::LookingForDefineWord => match &token
+            {
+                Token::WhiteSpace(_) | Token::LineContinuation(_) | Token::Comment(_) => {},
+                Token::Identifier(s) if matches!(s.as_str(), "define" | "if" | "elif" | "ifdef" | "ifndef" | "elifdef" | "elifndef") => {
+                    is_define = s == "define";
+                    state = State::LookingForNewLine;
+                },
+                _ => state = State::SkippingDirective,
+            },
+            State::LookingForNewLine if !matches!(token, Token::Comment(_)) => tokens.push(token),
+            _ => {},
+        }
+    }
+
+    res
+}
+
+/// Text of a directive after its name, without the name and the parameters of a defined macro,
+/// with line continuations replaced by spaces
+fn directive_body(tokens: &[Token], is_define: bool) -> String
+{
+    let mut start = 0;
+    if is_define
+    {
+        let is_space = |t: &Token| matches!(t, Token::WhiteSpace(_) | Token::LineContinuation(_));
+        start = tokens.iter().position(|t| !is_space(t)).map_or(tokens.len(), |i| i + 1);
+        // Parameters of a function-like macro follow its name without spaces
+        if tokens.get(start) == Some(&Token::Operator("(".into()))
+        {
+            start = tokens[start..].iter().position(|t| *t == Token::Operator(")".into())).map_or(tokens.len(), |i| start + i + 1);
+        }
+    }
+    tokens[start..]
+        .iter()
+        .map(|t| if let Token::LineContinuation(_) = t { " " } else { t.text() })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
 pub fn remove_preprocessor_directives(file_content: &str) -> String
 {
     let mut res = String::new();
@@ -415,5 +565,58 @@ int main()
         assert_eq!(get_custom_includes(input), Vec::<String>::new());
         assert_eq!(get_standard_includes(input), vec!["vector"]);
     }
-}
 
+    #[test]
+    fn test_directive_expressions()
+    {
+        let input = "\
+#include <cassert>
+#define CHECK(x) assert(x) // checked
+#define LIMIT \\
+    INT_MAX
+#if defined(EOF) && LIMIT > 0
+#elif __cplusplus
+#endif
+#undef CHECK
+#ifdef NDEBUG
+int x;
+#define EMPTY";
+        assert_eq!(get_directive_expressions(input), vec![
+            "assert(x)",
+            "INT_MAX",
+            "defined(EOF) && LIMIT > 0",
+            "__cplusplus",
+            "NDEBUG",
+            "",
+        ]);
+    }
+
+    #[test]
+    fn test_module_imports()
+    {
+        let input = "\
+module;
+#include <cassert>
+#define IMPORT(x) import x; \\
+    import y;
+export module shapes:core;
+import std;
+import <vector>;
+export import \"legacy.h\";
+import :detail [[deprecated]];
+int import;
+int f() { import = 1; }
+";
+        assert_eq!(get_includes_with_brackets(input), vec![
+            "<cassert>".to_string(),
+            "std".to_string(),
+            "<vector>".to_string(),
+            "\"legacy.h\"".to_string(),
+            ":detail".to_string(),
+        ]);
+        assert_eq!(get_includes(input), vec!["cassert", "std", "vector", "legacy.h", ":detail"]);
+        assert_eq!(get_standard_includes(input), vec!["cassert", "vector"]);
+        assert_eq!(get_custom_includes(input), vec!["legacy.h"]);
+        assert_eq!(get_imported_modules(input), vec!["std", ":detail"]);
+    }
+}
@@ -1,218 +1,542 @@
-use std::collections::HashMap;
 use crate::preprocessor;
-use crate::string_remover;
 use crate::comment_remover;
+use crate::declaration_finder::Dialect;
+use crate::header_db::{Availability, HeaderDb, Standard};
+use crate::iwyu;
+use crate::tokenizer::tokenize;
+use crate::tokenizer::Token;
 
-fn make_standard_headers_map() -> HashMap<&'static str, Vec<&'static str>>
-{
-    let mut res = HashMap::from([
-        ("array", vec!["array", "to_array"]),
-        ("vector", vec!["vector"]),
-        ("deque", vec!["deque"]),
-        ("forward_list", vec!["forward_list"]),
-        ("list", vec!["list"]),
-        ("stack", vec!["stack"]),
-        ("queue", vec!["queue", "priority_queue"]),
-        ("set", vec!["set", "multiset"]),
-        ("map", vec!["map", "multimap"]),
-        ("unordered_set", vec!["unordered_set", "unordered_multiset"]),
-        ("unordered_map", vec!["unordered_map", "unordered_multimap"]),
-        ("string", vec!["char_traits", "basic_string", "string", "u8string", "u16string", "u32string", "wstring", "getline", "stoi", "stol", "stoll", "stoul", "stoull", "stof", "stod", "stold", "to_string", "to_wstring"]),
-        ("chrono", vec!["chrono"]), // chrono is a namespace
-        ("sstream", vec!["basic_stringbuf", "basic_istringstream", "basic_ostringstream", "basic_stringstream", "stringbuf", "wstringbuf", "istringstream", "wistringstream", "ostringstream", "wostringstream", "stringstream", "wstringstream"]),
-        ("ios", vec!["ios_base", "basic_ios", "ios", "wios", "fpos", "io_errc", "is_error_code_enum", "streamoff", "streamsize", "iostream_category", "make_error_code", "make_error_condition", "boolalpha", "noboolalpha", "showbase", "noshowbase", "showpoint", "noshowpoint", "showpos", "noshowpos", "skipws", "noskipws", "uppercase", "nouppercase", "unitbuf", "nounitbuf", "internal", "left", "right", "dec", "hex", "oct", "fixed", "scientific", "hexfloat", "defaultfloat"]),
-        ("ostream", vec!["basic_ostream", "ostream", "wostream", "endl", "ends", "flush", "emit_on_flush", "noemit_on_flush", "flush_emit"]),
-        ("istream", vec!["basic_istream", "istream", "wistream", "basic_iostream", "iostream", "wiostream", "ws"]),
-        ("streambuf", vec!["basic_streambuf", "streambuf", "wstreambuf"]),
-        ("optional", vec!["optional", "bad_optional_access", "nullopt_t", "make_optional"]),
-        ("thread", vec!["this_thread", "thread", "jthread"]), // this_thread is a namespace
-        ("regex", vec!["regex_match", "regex_search", "regex_replace", "regex_iterator", "regex_token_iterator", "basic_regex", "match_results", "sub_match", "regex_traits", "regex_error", "regex", "wregex", "cmatch", "wcmatch", "smatch", "wsmatch", "csub_match", "wcsub_match", "ssub_match", "wssub_match", "regex_constants"]),
-        ("concepts", vec!["same_as", "derived_from", "convertible_to", "common_reference_with", "common_with", "integral", "signed_integral", "unsigned_integral", "floating_point", "assignable_from", "swappable", "swappable_with", "destructible", "constructible_from", "default_initializable", "move_constructible", "copy_constructible", "equality_comparable", "equality_comparable_with", "totally_ordered", "totally_ordered_with", "movable", "copyable", "semiregular", "regular", "invocable", "regular_invocable", "predicate", "relation", "equivalence_relation", "strict_weak_order"]), // ranges::swap ???
-        ("any", vec!["any", "bad_any_cast", "make_any", "any_cast"]),
-        ("compare", vec!["three_way_comparable", "three_way_comparable_with", "partial_ordering", "weak_ordering", "strong_ordering", "common_comparison_category", "compare_three_way_result", "compare_three_way", "strong_order", "weak_order", "partial_order", "compare_strong_order_fallback", "compare_weak_order_fallback", "compare_partial_order_fallback", "is_eq", "is_neq", "is_lt", "is_lteq", "is_gt", "is_gteq"]),
-        ("csetjmp", vec!["jmp_buf", "setjmp", "longjmp"]),
-        ("csignal", vec!["sig_atomic_t", "SIGABRT", "SIGFPE", "SIGILL", "SIGINT", "SIGSEGV", "SIGTERM", "SIG_DFL", "SIG_IGN", "SIG_ERR", "signal", "raise"]),
-        ("cstdarg", vec!["va_list", "va_start", "va_arg", "va_copy", "va_end"]),
-        ("cstddef", vec!["NULL", "offsetof", "size_t", "ptrdiff_t", "nullptr_t", "max_align_t", "byte"]),
-        ("cstdlib", vec!["div_t", "ldiv_t", "lldiv_t", "size_t", "EXIT_SUCCESS", "EXIT_FAILURE", "MB_CUR_MAX", "NULL", "RAND_MAX", "abort", "exit", "quick_exit", "_Exit", "atexit", "at_quick_exit", "system", "getenv", "malloc", "aligned_alloc", "calloc", "realloc", "free", "atof", "atoi", "atol", "atoll", "strtol", "strtoll", "strtoul", "strtoull", "strtof", "strtod", "strtold", "mblen", "mbtowc", "wctomb", "mbstowcs", "wcstombs", "rand", "srand", "qsort", "bsearch", "abs", "labs", "llabs", "div", "ldiv", "lldiv"]),
-        ("ctime", vec!["CLOCKS_PER_SEC", "NULL", "clock_t", "size_t", "time_t", "tm", "timespec", "clock", "time", "difftime", "timespec_get", "ctime", "asctime", "strftime", "wcsftime", "gmtime", "localtime", "mktime"]),
-        ("expected", vec!["expected", "unexpected", "bad_expected_access", "unexpect_t", "unexpect"]),
-        ("functional", vec!["placeholders", "function", "move_only_function", "mem_fn", "reference_wrapper", "unwrap_reference", "unwrap_ref_decay", "bad_function_call", "is_bind_expression", "is_placeholder", "plus", "minus", "multiplies", "divides", "", "modulus", "negate", "equal_to", "", "not_equal_to", "greater", "less", "greater_equal", "less_equal", "ranges", "compare_three_way", "logical_and", "logical_or", "logical_not", "bit_and", "bit_or", "bit_xor", "bit_not", "not_fn", "identity", "default_searcher", "boyer_moore_searcher", "boyer_moore_horspool_searcher", "bind_front", "bind_back", "bind", "ref", "cref", "invoke", "invoke_r", "unary_function", "binary_function", "binder1st", "binder2nd", "bind1st", "bind2nd", "pointer_to_unary_function", "pointer_to_binary_function", "ptr_fun", "mem_fun_t", "mem_fun1_t", "const_mem_fun_t", "const_mem_fun1_t", "mem_fun", "mem_fun_ref_t", "mem_fun1_ref_t", "const_mem_fun_ref_t", "const_mem_fun1_ref_t", "mem_fun_ref", "unary_negate", "binary_negate", "not1", "not2"]),
-        ("initializer_list", vec!["initializer_list"]),
-        ("source_location", vec!["source_location"]),
-        ("type_traits", vec!["integral_constant", "bool_constant", "true_type", "false_type", "is_void", "is_null_pointer", "is_integral", "is_floating_point", "is_array", "is_enum", "is_union", "is_class", "is_function", "is_pointer", "is_lvalue_reference", "is_rvalue_reference", "is_member_object_pointer", "is_member_function_pointer", "is_fundamental", "is_arithmetic", "is_scalar", "is_object", "is_compound", "is_reference", "is_member_pointer", "is_const", "is_volatile", "is_trivial", "is_trivially_copyable", "is_standard_layout", "is_pod", "is_literal_type", "has_unique_object_representations", "is_empty", "is_polymorphic", "is_abstract", "is_final", "is_aggregate", "is_signed", "is_unsigned", "is_bounded_array", "is_unbounded_array", "is_scoped_enum", "is_constructible", "is_trivially_constructible", "is_nothrow_constructible", "is_default_constructible", "is_trivially_default_constructible", "is_nothrow_default_constructible", "is_copy_constructible", "is_trivially_copy_constructible", "is_nothrow_copy_constructible", "is_move_constructible", "is_trivially_move_constructible", "is_nothrow_move_constructible", "is_assignable", "is_trivially_assignable", "is_nothrow_assignable", "is_copy_assignable", "is_trivially_copy_assignable", "is_nothrow_copy_assignable", "is_move_assignable", "is_trivially_move_assignable", "is_nothrow_move_assignable", "is_destructible", "is_trivially_destructible", "is_nothrow_destructible", "has_virtual_destructor", "is_swappable_with", "is_swappable", "is_nothrow_swappable_with", "is_nothrow_swappable", "alignment_of", "rank", "extent", "is_same", "is_base_of", "is_convertible", "is_nothrow_convertible", "is_layout_compatible", "is_pointer_interconvertible_base_of", "is_invocable", "is_invocable_r", "is_nothrow_invocable", "is_nothrow_invocable_r", "remove_cv", "remove_const", "remove_volatile", "add_cv", "add_const", "add_volatile", "remove_reference", "add_lvalue_reference", "add_rvalue_reference", "remove_pointer", "add_pointer", "make_signed", "make_unsigned", "remove_extent", "remove_all_extents", "aligned_storage", "aligned_union", "decay", "remove_cvref", "enable_if", "conditional", "common_type", "common_reference", "basic_common_reference", "underlying_type", "result_of", "invoke_result", "void_t", "type_identity", "conjunction", "disjunction", "", "negation", "is_pointer_interconvertible_with_class", "is_corresponding_member", "is_constant_evaluated"]),
-        ("typeinfo", vec!["type_info", "bad_typeid", "bad_cast"]),
-        ("memory_resource", vec!["polymorphic_allocator", "memory_resource", "pool_options", "synchronized_pool_resource", "unsynchronized_pool_resource", "monotonic_buffer_resource", "new_delete_resource", "null_memory_resource", "get_default_resource", "set_default_resource"]),
-        ("new", vec!["bad_alloc", "bad_array_new_length", "nothrow_t", "align_val_t", "destroying_delete_t", "new_handler", "nothrow", "destroying_delete", "hardware_destructive_interference_size", "hardware_constructive_interference_size", "get_new_handler", "set_new_handler", "launder"]),
-        ("scoped_allocator", vec!["scoped_allocator_adaptor"]),
-        ("cfloat", vec!["FLT_ROUNDS", "FLT_EVAL_METHOD", "FLT_HAS_SUBNORM", "DBL_HAS_SUBNORM", "LDBL_HAS_SUBNORM", "FLT_RADIX", "FLT_MANT_DIG", "DBL_MANT_DIG", "LDBL_MANT_DIG", "FLT_DECIMAL_DIG", "DBL_DECIMAL_DIG", "LDBL_DECIMAL_DIG", "DECIMAL_DIG", "FLT_DIG", "DBL_DIG", "LDBL_DIG", "FLT_MIN_EXP", "DBL_MIN_EXP", "LDBL_MIN_EXP", "FLT_MIN_10_EXP", "DBL_MIN_10_EXP", "LDBL_MIN_10_EXP", "FLT_MAX_EXP", "DBL_MAX_EXP", "LDBL_MAX_EXP", "FLT_MAX_10_EXP", "DBL_MAX_10_EXP", "LDBL_MAX_10_EXP", "FLT_MAX", "DBL_MAX", "LDBL_MAX", "FLT_EPSILON", "DBL_EPSILON", "LDBL_EPSILON", "FLT_MIN", "DBL_MIN", "LDBL_MIN", "FLT_TRUE_MIN", "DBL_TRUE_MIN", "LDBL_TRUE_MIN"]),
-        ("climits", vec!["CHAR_BIT", "SCHAR_MIN", "SCHAR_MAX", "UCHAR_MAX", "CHAR_MIN", "CHAR_MAX", "MB_LEN_MAX", "SHRT_MIN", "SHRT_MAX", "USHRT_MAX", "INT_MIN", "INT_MAX", "UINT_MAX", "LONG_MIN", "LONG_MAX", "ULONG_MAX", "LLONG_MIN", "LLONG_MAX", "ULLONG_MAX"]),
-        ("cstdint", vec!["int8_t", "int16_t", "int32_t", "int64_t", "int_fast8_t", "int_fast16_t", "int_fast32_t", "int_fast64_t", "int_least8_t", "int_least16_t", "int_least32_t", "int_least64_t", "intmax_t", "intptr_t", "uint8_t", "uint16_t", "uint32_t", "uint64_t", "uint_fast8_t", "uint_fast16_t", "uint_fast32_t", "uint_fast64_t", "uint_least8_t", "uint_least16_t", "uint_least32_t", "uint_least64_t", "uintmax_t", "uintptr_t", "INTN_MIN", "INTN_MAX", "UINTN_MAX", "INT_FASTN_MIN", "INT_FASTN_MAX", "UINT_FASTN_MAX", "INT_LEASTN_MIN", "INT_LEASTN_MAX", "UINT_LEASTN_MAX", "INTMAX_MIN", "INTMAX_MAX", "UINTMAX_MAX", "INTPTR_MIN", "INTPTR_MAX", "UINTPTR_MAX", "PTRDIFF_MIN", "PTRDIFF_MAX", "SIZE_MAX", "SIG_ATOMIC_MIN", "SIG_ATOMIC_MAX", "WCHAR_MIN", "WCHAR_MAX", "WINT_MIN", "WINT_MAX", "INTN_C", "UINTN_C", "INTMAX_C", "UINTMAX_C"]),
-        ("limits", vec!["numeric_limits", "float_round_style", "float_denorm_style"]),
-        ("cassert", vec!["assert"]),
-        ("cerrno", vec!["errno", "E2BIG", "EACCES", "EADDRINUSE", "EADDRNOTAVAIL", "EAFNOSUPPORT", "EAGAIN", "EALREADY", "EBADF", "EBADMSG", "EBUSY", "ECANCELED", "ECHILD", "ECONNABORTED", "ECONNREFUSED", "ECONNRESET", "EDEADLK", "EDESTADDRREQ", "EDOM", "EEXIST", "EFAULT", "EFBIG", "EHOSTUNREACH", "EIDRM", "EILSEQ", "EINPROGRESS", "EINTR", "EINVAL", "EIO", "EISCONN", "EISDIR", "ELOOP", "EMFILE", "EMLINK", "EMSGSIZE", "ENAMETOOLONG", "ENETDOWN", "ENETRESET", "ENETUNREACH", "ENFILE", "ENOBUFS", "ENODATA", "ENODEV", "ENOENT", "ENOEXEC", "ENOLCK", "ENOLINK", "ENOMEM", "ENOMSG", "ENOPROTOOPT", "ENOSPC", "ENOSR", "ENOSTR", "ENOSYS", "ENOTCONN", "ENOTDIR", "ENOTEMPTY", "ENOTRECOVERABLE", "ENOTSOCK", "ENOTSUP", "ENOTTY", "ENXIO", "EOPNOTSUPP", "EOVERFLOW", "EOWNERDEAD", "EPERM", "EPIPE", "EPROTO", "EPROTONOSUPPORT", "EPROTOTYPE", "ERANGE", "EROFS", "ESPIPE", "ESRCH", "ETIME", "ETIMEDOUT", "ETXTBSY", "EWOULDBLOCK", "EXDEV"]),
-        ("exception", vec!["exception", "nested_exception", "bad_exception", "unexpected_handler", "terminate_handler", "exception_ptr", "unexpected", "uncaught_exception", "uncaught_exceptions", "make_exception_ptr", "current_exception", "rethrow_exception", "throw_with_nested", "rethrow_if_nested", "terminate", "get_terminate", "set_terminate", "get_unexpected", "set_unexpected"]),
-        ("stacktrace", vec!["stacktrace_entry", "basic_stacktrace", "stacktrace"]), // to_string, operator<<
-        ("stdexcept", vec!["logic_error", "invalid_argument", "domain_error", "length_error", "out_of_range", "runtime_error", "range_error", "overflow_error", "underflow_error"]),
-        ("cctype", vec!["isalnum", "isalpha", "islower", "isupper", "isdigit", "isxdigit", "iscntrl", "isgraph", "isspace", "isblank", "isprint", "ispunct", "tolower", "toupper"]),
-        ("charconv", vec!["chars_format", "from_chars", "to_chars"]),
-        ("cstring", vec!["NULL", "size_t", "strcpy", "strncpy", "strcat", "strncat", "strxfrm", "strlen", "strcmp", "strncmp", "strcoll", "strchr", "strrchr", "strspn", "strcspn", "strpbrk", "strstr", "strtok", "memchr", "memcmp", "memset", "memcpy", "memmove", "strerror"]),
-        ("cuchar", vec!["__STDC_UTF_16__", "__STDC_UTF_32__", "mbstate_t", "size_t", "mbrtoc16", "c16rtomb", "mbrtoc32", "c32rtomb", "mbrtoc8", "c8rtomb"]),
-        ("cwchar", vec!["NULL", "WEOF", "WCHAR_MIN", "WCHAR_MAX", "mbstate_t", "size_t", "wint_t", "tm", "wcscpy", "wcsncpy", "wcscat", "wcsncat", "wcsxfrm", "wcslen", "wcscmp", "wcsncmp", "wcscoll", "wcschr", "wcsrchr", "wcsspn", "wcscspn", "wcspbrk", "wcsstr", "wcstok", "wmemcpy", "wmemmove", "wmemcmp", "wmemchr", "wmemset", "mbsinit", "btowc", "wctob", "mbrlen", "mbrtowc", "wcrtomb", "mbsrtowcs", "wcsrtombs", "fgetwc", "getwc", "fgetws", "fputwc", "putwc", "fputws", "getwchar", "putwchar", "ungetwc", "fwide", "wscanf", "fwscanf", "swscanf", "vwscanf", "vfwscanf", "vswscanf", "wprintf", "fwprintf", "swprintf", "vwprintf", "vfwprintf", "vswprintf", "wcsftime", "wcstol", "wcstoll", "wcstoul", "wcstoull", "wcstof", "wcstod", "wcstold"]),
-        ("cwctype", vec!["wctrans_t", "wctype_t", "wint_t", "WEOF", "iswalnum", "iswalpha", "iswlower", "iswupper", "iswdigit", "iswxdigit", "iswcntrl", "iswgraph", "iswspace", "iswblank", "iswprint", "iswpunct", "iswctype", "wctype", "towlower", "towupper", "towctrans", "wctrans"]),
-        ("format", vec!["formatter", "basic_format_parse_context", "format_parse_context", "wformat_parse_context", "basic_format_context", "format_context", "wformat_context", "basic_format_arg", "basic_format_args", "format_args", "wformat_args", "format_error", "format", "format_to", "format_to_n", "formatted_size", "vformat", "vformat_to", "visit_format_arg", "make_format_args", "make_wformat_args"]),
-        ("span", vec!["span", "dynamic_extent", "as_bytes", "as_writable_bytes"]),
-        ("iterator", vec!["indirectly_readable", "indirectly_writable", "weakly_incrementable", "incrementable", "input_or_output_iterator", "sentinel_for", "sized_sentinel_for", "input_iterator", "output_iterator", "forward_iterator", "bidirectional_iterator", "random_access_iterator", "contiguous_iterator", "indirectly_unary_invocable", "indirectly_regular_unary_invocable", "indirect_unary_predicate", "indirect_binary_predicate", "indirect_equivalence_relation", "indirect_strict_weak_order", "indirectly_movable", "indirectly_movable_storable", "indirectly_copyable", "indirectly_copyable_storable", "indirectly_swappable", "indirectly_comparable", "permutable", "mergeable", "sortable", "indirect_result_t", "projected", "incrementable_traits", "indirectly_readable_traits", "iter_value_t", "iter_reference_t", "iter_const_reference_t", "iter_difference_t", "iter_rvalue_reference_t", "iter_common_reference_t", "iterator_traits", "input_iterator_tag", "output_iterator_tag", "forward_iterator_tag", "bidirectional_iterator_tag", "random_access_iterator_tag", "contiguous_iterator_tag", "iterator", "reverse_iterator", "move_iterator", "move_sentinel", "common_iterator", "default_sentinel_t", "counted_iterator", "unreachable_sentinel_t", "back_insert_iterator", "front_insert_iterator", "insert_iterator", "istream_iterator", "ostream_iterator", "istreambuf_iterator", "ostreambuf_iterator", "iter_move", "iter_swap", "unreachable_sentinel", "default_sentinel", "make_reverse_iterator", "make_move_iterator", "front_inserter", "back_inserter", "inserter", "advance", "distance", "next", "prev", "ranges::advance", "ranges::distance", "ranges::next", "ranges::prev"]),
-        ("ranges", vec!["ranges::range", "ranges::borrowed_range", "ranges::sized_range", "ranges::view", "ranges::input_range", "ranges::output_range", "ranges::forward_range", "ranges::bidirectional_range", "ranges::random_access_range", "ranges::contiguous_range", "ranges::common_range", "ranges::viewable_range", "ranges::constant_range", "ranges::to", "ranges::iterator_t", "ranges::const_iterator_t", "ranges::sentinel_t", "ranges::range_difference_t", "ranges::range_size_t", "ranges::range_value_t", "ranges::range_reference_t", "ranges::range_const_reference_t", "ranges::range_rvalue_reference_t", "ranges::view_interface", "ranges::subrange", "ranges::dangling", "ranges::borrowed_iterator_t", "ranges::borrowed_subrange_t", "ranges::range_adaptor_closure", "ranges::empty_view", "views::empty", "ranges::single_view", "views::single", "ranges::iota_view", "views::iota", "ranges::basic_istream_view", "views::istream", "ranges::repeat_view", "views::repeat", "ranges::cartesian_product_view", "views::cartesian_product", "views::all_t", "views::all", "ranges::ref_view", "ranges::owning_view", "ranges::filter_view", "views::filter", "ranges::transform_view", "views::transform", "ranges::take_view", "views::take", "ranges::take_while_view", "views::take_while", "ranges::drop_view", "views::drop", "ranges::drop_while_view", "views::drop_while", "ranges::join_view", "views::join", "ranges::lazy_split_view", "views::lazy_split", "ranges::split_view", "views::split", "views::counted", "ranges::common_view", "views::common", "ranges::reverse_view", "views::reverse", "ranges::as_const_view", "views::as_const", "ranges::as_rvalue_view", "views::as_rvalue", "ranges::elements_view", "views::elements", "ranges::keys_view", "views::keys", "ranges::values_view", "views::values", "ranges::zip_view", "views::zip", "ranges::zip_transform_view", "views::zip_transform", "ranges::adjacent_view", "views::adjacent", "ranges::adjacent_transform_view", "views::adjacent_transform", "ranges::join_with_view", "views::join_with", "ranges::stride_view", "views::stride", "ranges::slide_view", "views::slide", "ranges::chunk_view", "views::chunk", "ranges::chunk_by_view", "views::chunk_by"]),
-        ("execution", vec!["is_execution_policy", "sequenced_policy", "parallel_policy", "parallel_unsequenced_policy", "unsequenced_policy", "seq", "par", "par_unseq", "unseq"]),
-        ("bit", vec!["endian", "bit_cast", "byteswap", "has_single_bit", "bit_ceil", "bit_floor", "bit_width", "rotl", "rotr", "countl_zero", "countl_one", "countr_zero", "countr_one", "popcount"]),
-        ("cfenv", vec!["fenv_t", "fexcept_t", "feclearexcept", "fetestexcept", "feraiseexcept", "fegetexceptflag", "fesetexceptflag", "fegetround", "fesetround", "fegetenv", "fesetenv", "feholdexcept", "feupdateenv", "FE_ALL_EXCEPT", "FE_DIVBYZERO", "FE_INEXACT", "FE_INVALID", "FE_OVERFLOW", "FE_UNDERFLOW", "FE_DOWNWARD", "FE_TONEAREST", "FE_TOWARDZERO", "FE_UPWARD", "FE_DFL_ENV"]),
-        ("complex", vec!["complex", "real", "imag", "arg", "norm", "conj", "proj", "polar", "if", "i", "il"]), // and lots of math functions taking complex arguments
-        ("numeric", vec!["iota", "ranges::iota", "accumulate", "reduce", "transform_reduce", "inner_product", "adjacent_difference", "partial_sum", "inclusive_scan", "exclusive_scan", "transform_inclusive_scan", "transform_exclusive_scan", "gcd", "lcm", "midpoint"]),
-        ("cmath", vec!["float_t", "double_t", "HUGE_VALF", "HUGE_VAL", "HUGE_VALL", "INFINITY", "NAN", "math_errhandling", "MATH_ERRNO", "MATH_ERREXCEPT", "FP_NORMAL", "FP_SUBNORMAL", "FP_ZERO", "FP_INFINITE", "FP_NAN", "fabs", "fabsf", "fabsl", "fmod", "fmodf", "fmodl", "remainder", "remainderf", "remainderl", "remquo", "remquof", "remquol", "fma", "fmaf", "fmal", "fmax", "fmaxf", "fmaxl", "fmin", "fminf", "fminl", "fdim", "fdimf", "fdiml", "nan", "nanf", "nanl", "lerp", "exp", "expf", "expl", "exp2", "exp2f", "exp2l", "expm1", "expm1f", "expm1l", "log", "logf", "logl", "log10", "log10f", "log10l", "log2", "log2f", "log2l", "log1p", "log1pf", "log1pl", "pow", "powf", "powl", "sqrt", "sqrtf", "sqrtl", "cbrt", "cbrtf", "cbrtl", "hypot", "hypotf", "hypotl", "sin", "sinf", "sinl", "cos", "cosf", "cosl", "tan", "tanf", "tanl", "asin", "asinf", "asinl", "acos", "acosf", "acosli", "atan", "atanf", "atanl", "atan2", "atan2f", "atan2l", "sinh", "sinhf", "sinhl", "cosh", "coshf", "coshl", "tanh", "tanhf", "tanhl", "asinh", "asinhf", "asinhl", "acosh", "acoshf", "acoshl", "atanh", "atanhf", "atanhl", "erf", "erff", "erfl", "erfc", "erfcf", "erfcl", "tgamma", "tgammaf", "tgammal", "lgamma", "lgammaf", "lgammal", "ceil", "ceilf", "ceill", "floor", "floorf", "floorl", "trunc", "truncf", "truncl", "round", "roundf", "roundl", "lround", "lroundf", "lroundl", "llround", "llroundf", "llroundl", "nearbyint", "nearbyintf", "nearbyintl", "rint", "rintf", "rintl", "lrint", "lrintf", "lrintl", "llrint", "llrintf", "llrintl", "frexp", "frexpf", "frexpl", "ldexp", "ldexpf", "ldexpl", "modf", "modff", "modfl", "scalbn", "scalbnf", "scalbnl", "scalbln", "scalblnf", "scalblnl", "ilogb", "ilogbf", "ilogbl", "logb", "logbf", "logbl", "nextafter", "nextafterf", "nextafterl", "nexttoward", "nexttowardf", "nexttowardl", "copysign", "copysignf", "copysignl", "fpclassify", "isfinite", "isinf", "isnan", "isnormal", "signbit", "isgreater", "isgreaterequal", "isless", "islessequal", "islessgreater", "isunordered", "assoc_laguerre", "assoc_laguerref", "assoc_laguerrel", "assoc_legendre", "assoc_legendref", "assoc_legendrel", "beta", "betaf", "betal", "comp_ellint_1", "comp_ellint_1f", "comp_ellint_1l", "comp_ellint_2", "comp_ellint_2f", "comp_ellint_2l", "comp_ellint_3", "comp_ellint_3f", "comp_ellint_3l", "cyl_bessel_i", "cyl_bessel_if", "cyl_bessel_il", "cyl_bessel_j", "cyl_bessel_jf", "cyl_bessel_jl", "cyl_bessel_k", "cyl_bessel_kf", "cyl_bessel_kl", "cyl_neumann", "cyl_neumannf", "cyl_neumannl", "ellint_1", "ellint_1f", "ellint_1l", "ellint_2", "ellint_2f", "ellint_2l", "ellint_3", "ellint_3f", "ellint_3l", "expint", "expintf", "expintl", "hermite", "hermitef", "hermitel", "legendre", "legendref", "legendrel", "laguerre", "laguerref", "laguerrel", "riemann_zeta", "riemann_zetaf", "riemann_zetal", "sph_bessel", "sph_besself", "sph_bessell", "sph_legendre", "sph_legendref", "sph_legendrel", "sph_neumann", "sph_neumannf", "sph_neumannl"]),
-        ("ratio", vec!["ratio", "ratio_add", "ratio_subtract", "ratio_multiply", "ratio_divide", "ratio_equal", "ratio_not_equal", "ratio_less", "ratio_less_equal", "ratio_greater", "ratio_greater_equal", "yocto", "zepto", "atto", "femto", "pico", "nano", "micro", "milli", "centi", "deci", "deca", "hecto", "kilo", "mega", "giga", "tera", "peta", "exa", "zetta", "yotta"]),
-        ("clocale", vec!["lconv", "NULL", "LC_ALL", "LC_COLLATE", "LC_CTYPE", "LC_MONETARY", "LC_NUMERIC", "LC_TIME", "setlocale", "localeconv"]),
-        ("codecvt", vec!["codecvt_utf8", "codecvt_utf16", "codecvt_utf8_utf16", "codecvt_mode"]),
-        ("locale", vec!["locale", "wstring_convert", "wbuffer_convert", "ctype_base", "codecvt_base", "messages_base", "time_base", "money_base", "ctype", "codecvt", "collate", "messages", "time_get", "time_put", "num_get", "num_put", "numpunct", "money_get", "money_put", "moneypunct", "ctype_byname", "codecvt_byname", "messages_byname", "collate_byname", "time_get_byname", "time_put_byname", "numpunct_byname", "moneypunct_byname", "use_facet", "has_facet"]),
-        ("cstdio", vec!["FILE", "fpos_t", "size_t", "NULL", "stdin", "stdout", "stderr", "EOF", "FOPEN_MAX", "FILENAME_MAX", "BUFSIZ", "_IOFBF", "_IOLBF", "_IONBF", "SEEK_SET", "SEEK_CUR", "SEEK_END", "TMP_MAX", "L_tmpnam", "fopen", "freopen", "fclose", "fflush", "setbuf", "setvbuf", "fread", "fwrite", "fgetc", "getc", "fgets", "fputc", "putc", "fputs", "getchar", "gets", "putchar", "puts", "ungetc", "scanf", "fscanf", "sscanf", "vscanf", "vfscanf", "vsscanf", "printf", "fprintf", "sprintf", "snprintf", "vprintf", "vfprintf", "vsprintf", "vsnprintf", "ftell", "fgetpos", "fseek", "fsetpos", "rewind", "clearerr", "feof", "ferror", "perror", "remove", "rename", "tmpfile", "tmpnam"]),
-        ("fstream", vec!["basic_filebuf", "basic_ifstream", "basic_ofstream", "basic_fstream", "filebuf", "wfilebuf", "ifstream", "wifstream", "ofstream", "wofstream", "fstream", "wfstream"]),
-        ("iomanip", vec!["resetiosflags", "setiosflags", "setbase", "setfill", "setprecision", "setw", "get_money", "put_money", "get_time", "put_time", "quoted"]),
-        ("spanstream", vec!["basic_spanbuf", "basic_ispanstream", "basic_ospanstream", "basic_spanstream", "spanbuf", "wspanbuf", "ispanstream", "wispanstream", "ospanstream", "wospanstream", "spanstream", "wspanstream"]),
-        ("strstream", vec!["strstreambuf", "istrstream", "ostrstream", "strstream"]),
-        ("mutex", vec!["mutex", "timed_mutex", "recursive_mutex", "recursive_timed_mutex", "lock_guard", "unique_lock", "scoped_lock", "defer_lock_t", "try_to_lock_t", "adopt_lock_t", "once_flag", "defer_lock", "try_to_lock", "adopt_lock", "try_lock", "lock", "call_once"]),
-        ("semaphore", vec!["counting_semaphore", "binary_semaphore"]),
-        ("shared_mutex", vec!["shared_mutex", "shared_timed_mutex", "shared_lock"]),
-        ("stop_token", vec!["stop_token", "stop_source", "stop_callback", "nostopstate_t", "nostopstate"]),
-        ("latch", vec!["latch"]),
-        ("future", vec!["promise", "packaged_task", "future", "shared_future", "launch", "future_status", "future_error", "future_errc", "async", "future_category"]),
-        ("condition_variable", vec!["condition_variable", "condition_variable_any", "cv_status", "notify_all_at_thread_exit"]),
-        ("barrier", vec!["barrier"]),
-        ("atomic", vec!["atomic", "atomic_ref", "atomic_flag", "memory_order", "atomic_bool", "atomic_char", "atomic_schar", "atomic_uchar", "atomic_short", "atomic_ushort", "atomic_int", "atomic_uint", "atomic_long", "atomic_ulong", "atomic_llong", "atomic_ullong", "atomic_char8_t", "atomic_char16_t", "atomic_char32_t", "atomic_wchar_t", "atomic_int8_t", "atomic_uint8_t", "atomic_int16_t", "atomic_uint16_t", "atomic_int32_t", "atomic_uint32_t", "atomic_int64_t", "atomic_uint64_t", "atomic_int_least8_t", "atomic_uint_least8_t", "atomic_int_least16_t", "atomic_uint_least16_t", "atomic_int_least32_t", "atomic_uint_least32_t", "atomic_int_least64_t", "atomic_uint_least64_t", "atomic_int_fast8_t", "atomic_uint_fast8_t", "atomic_int_fast16_t", "atomic_uint_fast16_t", "atomic_int_fast32_t", "atomic_uint_fast32_t", "atomic_int_fast64_t", "atomic_uint_fast64_t", "atomic_intptr_t", "atomic_uintptr_t", "atomic_size_t", "atomic_ptrdiff_t", "atomic_intmax_t", "atomic_uintmax_t", "atomic_signed_lock_free", "atomic_unsigned_lock_free", "atomic_is_lock_free", "atomic_store", "atomic_store_explicit", "atomic_load", "atomic_load_explicit", "atomic_exchange", "atomic_exchange_explicit", "atomic_compare_exchange_weak", "atomic_compare_exchange_weak_explicit", "atomic_compare_exchange_strong", "atomic_compare_exchange_strong_explicit", "atomic_fetch_add", "atomic_fetch_add_explicit", "atomic_fetch_sub", "atomic_fetch_sub_explicit", "atomic_fetch_and", "atomic_fetch_and_explicit", "atomic_fetch_or", "atomic_fetch_or_explicit", "atomic_fetch_xor", "atomic_fetch_xor_explicit", "atomic_wait", "atomic_wait_explicit", "atomic_notify_one", "atomic_notify_all", "atomic_flag_test", "atomic_flag_test_explicit", "atomic_flag_test_and_set", "atomic_flag_test_and_set_explicit", "atomic_flag_clear", "atomic_flag_clear_explicit", "atomic_flag_wait", "atomic_flag_wait_explicit", "atomic_flag_notify_one", "atomic_flag_notify_all", "atomic_init", "kill_dependency", "atomic_thread_fence", "atomic_signal_fence", "ATOMIC_VAR_INIT", "ATOMIC_FLAG_INIT"]),
-        ("iso646.h", vec!["and", "and_eq", "bitand", "bitor", "compl", "not", "not_eq", "or", "or_eq", "xor", "xor_eq"]),
-        ("stdbool.h", vec!["bool", "true", "false", "_Bool"]),
-    ]);
-
-    let mut iostream = vec!["cin", "wcin", "cout", "wcout", "cerr", "wcerr", "clog", "wclog"];
-    iostream.extend(&res["ios"]);
-    iostream.extend(&res["streambuf"]);
-    iostream.extend(&res["istream"]);
-    iostream.extend(&res["ostream"]);
-    res.insert("iostream", iostream);
-
-    let mut coroutine = vec!["coroutine_traits", "coroutine_handle", "noop_coroutine_promise", "noop_coroutine_handle", "suspend_never", "suspend_always", "noop_coroutine"];
-    coroutine.extend(&res["compare"]);
-    res.insert("coroutine", coroutine);
-
-    let mut bitset = vec!["bitset"];
-    bitset.extend(&res["string"]);
-    // bitset.extend(&res["iosfwd"]); // TODO
-    res.insert("bitset", bitset);
-
-    let mut tuple = vec!["tuple", "tuple_size", "tuple_element", "tuple_size", "tuple_element", "uses_allocator", "ignore", "make_tuple", "tie", "forward_as_tuple", "tuple_cat", "get", "apply", "make_from_tuple"];
-    tuple.extend(&res["compare"]);
-    res.insert("tuple", tuple);
-
-    let mut typeindex = vec!["type_index"];
-    typeindex.extend(&res["compare"]);
-    res.insert("typeindex", typeindex);
-
-    let mut bad_cast = vec!["rel_ops", "swap", "exchange", "forward", "forward_like", "move", "move_if_noexcept", "as_const", "declval", "to_underlying", "cmp_equal", "cmp_not_equal", "cmp_less", "cmp_greater", "cmp_less_equal", "cmp_greater_equal", "in_range", "unreachable", "make_pair", "pair", "tuple_size", "tuple_element", "integer_sequence", "piecewise_construct_t", "piecewise_construct", "in_place", "in_place_type", "in_place_index", "in_place_t", "in_place_type_t", "in_place_index_t"];
-    bad_cast.extend(&res["compare"]);
-    bad_cast.extend(&res["initializer_list"]);
-    res.insert("bad_cast", bad_cast);
-
-    let mut variant = vec!["variant", "monostate", "bad_variant_access", "variant_size", "variant_size_v", "variant_alternative", "variant_alternative_t", "variant_npos", "visit", "holds_alternative", "get_if"];
-    variant.extend(&res["compare"]);
-    res.insert("variant", variant);
-
-    let mut memory = vec!["pointer_traits", "pointer_safety", "allocator", "allocator_traits", "allocation_result", "allocator_arg_t", "uses_allocator", "raw_storage_iterator", "unique_ptr", "shared_ptr", "weak_ptr", "auto_ptr", "owner_less", "enable_shared_from_this", "bad_weak_ptr", "default_delete", "out_ptr_t", "inout_ptr_t", "allocator_arg", "allocate_at_least", "to_address", "addressof", "align", "assume_aligned", "declare_reachable", "undeclare_reachable", "declare_no_pointers", "undeclare_no_pointers", "get_pointer_safety", "uninitialized_copy", "uninitialized_copy_n", "uninitialized_fill", "uninitialized_fill_n", "uninitialized_move", "uninitialized_move_n", "uninitialized_default_construct", "uninitialized_default_construct_n", "uninitialized_value_construct", "uninitialized_value_construct_n", "construct_at", "destroy_at", "destroy", "destroy_n", "get_temporary_buffer", "return_temporary_buffer", "make_unique", "make_unique_for_overwrite", "make_shared", "make_shared_for_overwrite", "allocate_shared", "allocate_shared_for_overwrite", "static_pointer_cast", "dynamic_pointer_cast", "const_pointer_cast", "reinterpret_pointer_cast", "get_deleter", "out_ptr", "inout_ptr", "ranges::uninitialized_copy", "ranges::uninitialized_copy_n", "ranges::uninitialized_fill", "ranges::uninitialized_fill_n", "ranges::uninitialized_move", "ranges::uninitialized_move_n", "ranges::uninitialized_default_construct", "ranges::uninitialized_default_construct_n", "ranges::uninitialized_value_construct", "ranges::uninitialized_value_construct_n", "ranges::construct_at", "ranges::destroy_at", "ranges::destroy", "ranges::destroy_n"]; // operator<<
-    memory.extend(&res["compare"]);
-    res.insert("memory", memory);
-
-    let cinttypes = vec!["imaxdiv_t", "abs", "imaxabs", "div", "imaxdiv", "strtoimax", "strtoumax", "wcstoimax", "wcstoumax", "PRIdN", "PRIiN", "PRIoN", "PRIuN", "PRIxN", "PRIXN", "SCNdN", "SCNiN", "SCNoN", "SCNuN", "SCNxN", "PRIdLEASTN", "PRIiLEASTN", "PRIoLEASTN", "PRIuLEASTN", "PRIxLEASTN", "PRIXLEASTN", "SCNdLEASTN", "SCNiLEASTN", "SCNoLEASTN", "SCNuLEASTN", "SCNxLEASTN", "PRIdFASTN", "PRIiFASTN", "PRIoFASTN", "PRIuFASTN", "PRIxFASTN", "PRIXFASTN", "SCNdFASTN", "SCNiFASTN", "SCNoFASTN", "SCNuFASTN", "SCNxFASTN", "PRIdMAX", "PRIiMAX", "PRIoMAX", "PRIuMAX", "PRIxMAX", "PRIXMAX", "SCNdMAX", "SCNiMAX", "SCNoMAX", "SCNuMAX", "SCNxMAX", "PRIdPTR", "PRIiPTR", "PRIoPTR", "PRIuPTR", "PRIxPTR", "PRIXPTR", "SCNdPTR", "SCNiPTR", "SCNoPTR", "SCNuPTR", "SCNxPTR"];
-    // cinttypes.extend(&res["inttypes.h"]); // TODO
-    res.insert("cinttypes", cinttypes);
-
-    let mut system_error = vec!["error_category", "error_condition", "errc", "error_code", "system_error", "is_error_code_enum", "is_error_condition_enum", "generic_category", "system_category", "make_error_code", "make_error_condition"];
-    system_error.extend(&res["compare"]);
-    res.insert("system_error", system_error);
-
-    let mut string_view = vec!["basic_string_view", "string_view", "u8string_view", "u16string_view", "u32string_view", "wstring_view", "sv"]; // operator""sv
-    string_view.extend(&res["compare"]);
-    res.insert("string_view", string_view);
-
-    let mut algorithm = vec!["ranges::in_fun_result", "ranges::in_in_result", "ranges::in_out_result", "ranges::in_in_out_result", "ranges::in_out_out_result", "ranges::min_max_result", "ranges::in_found_result", "ranges::in_value_result", "ranges::out_value_result", "all_of", "any_of", "none_of", "for_each", "for_each_n", "count", "count_if", "mismatch", "find", "find_if", "find_if_not", "find_end", "find_first_of", "adjacent_find", "search", "search_n", "copy", "copy_if", "copy_n", "copy_backward", "move", "move_backward", "fill", "fill_n", "transform", "generate", "generate_n", "remove", "remove_if", "remove_copy", "remove_copy_if", "replace", "replace_if", "replace_copy", "replace_copy_if", "swap", "swap_ranges", "iter_swap", "reverse", "reverse_copy", "rotate", "rotate_copy", "shift_left", "shift_right", "random_shuffle", "shuffle", "sample", "unique", "unique_copy", "is_partitioned", "partition", "partition_copy", "stable_partition", "partition_point", "is_sorted", "is_sorted_until", "sort", "partial_sort", "partial_sort_copy", "stable_sort", "nth_element", "lower_bound", "upper_bound", "binary_search", "equal_range", "merge", "inplace_merge", "includes", "set_difference", "set_intersection", "set_symmetric_difference", "set_union", "is_heap", "is_heap_until", "make_heap", "push_heap", "pop_heap", "sort_heap", "max", "max_element", "min", "min_element", "minmax", "minmax_element", "clamp", "equal", "lexicographical_compare", "lexicographical_compare_three_way", "is_permutation", "next_permutation", "prev_permutation"]; // Hopefully, ranges::XXXX functions correspond to functions mentioned here.
-    algorithm.extend(&res["initializer_list"]);
-    res.insert("algorithm", algorithm);
-
-    let mut random = vec!["uniform_random_bit_generator", "linear_congruential_engine", "mersenne_twister_engine", "subtract_with_carry_engine", "discard_block_engine", "independent_bits_engine", "shuffle_order_engine", "minstd_rand0", "minstd_rand", "mt19937", "mt19937_64", "ranlux24_base", "ranlux48_base", "ranlux24", "ranlux48", "knuth_b", "default_random_engine", "random_device", "uniform_int_distribution", "uniform_real_distribution", "bernoulli_distribution", "binomial_distribution", "negative_binomial_distribution", "geometric_distribution", "poisson_distribution", "exponential_distribution", "gamma_distribution", "weibull_distribution", "extreme_value_distribution", "normal_distribution", "lognormal_distribution", "chi_squared_distribution", "cauchy_distribution", "fisher_f_distribution", "student_t_distribution", "discrete_distribution", "piecewise_constant_distribution", "piecewise_linear_distribution", "seed_seq", "generate_canonical"];
-    random.extend(&res["initializer_list"]);
-    res.insert("random", random);
-
-    let mut valarray = vec!["valarray", "slice", "slice_array", "gslice", "gslice_array", "mask_array", "indirect_array"];
-    valarray.extend(&res["initializer_list"]);
-    res.insert("valarray", valarray);
-
-    let mut syncstream = vec!["basic_syncbuf", "basic_osyncstream", "syncbuf", "wsyncbuf", "osyncstream", "wosyncstream"];
-    syncstream.extend(&res["ostream"]);
-    res.insert("syncstream", syncstream);
-
-    let mut filesystem = vec!["path", "filesystem_error", "directory_entry", "directory_iterator", "recursive_directory_iterator", "file_status", "space_info", "file_type", "perms", "perm_options", "copy_options", "directory_options", "file_time_type", "filesystem"];
-    filesystem.extend(&res["compare"]);
-    res.insert("filesystem", filesystem);
-
-    res.insert("assert.h", res["cassert"].clone());
-    res.insert("ctype.h", res["cctype"].clone());
-    res.insert("errno.h", res["cerrno"].clone());
-    res.insert("float.h", res["cfloat"].clone());
-    res.insert("limits.h", res["climits"].clone());
-    res.insert("locale.h", res["clocale"].clone());
-    res.insert("math.h", res["cmath"].clone());
-    res.insert("setjmp.h", res["csetjmp"].clone());
-    res.insert("signal.h", res["csignal"].clone());
-    res.insert("stdarg.h", res["cstdarg"].clone());
-    res.insert("stddef.h", res["cstddef"].clone());
-    res.insert("stdint.h", res["cstdint"].clone());
-    res.insert("stdio.h", res["cstdio"].clone());
-    res.insert("stdlib.h", res["cstdlib"].clone());
-    res.insert("string.h", res["cstring"].clone());
-    res.insert("time.h", res["ctime"].clone());
-    res.insert("wchar.h", res["cwchar"].clone());
-    res.insert("wctype.h", res["cwctype"].clone());
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
+/// Find standard headers included, but not used in a file, in the order they are included
+pub fn get_unused_headers(file_content: &str) -> Vec<String>
+{
+    get_unused_headers_with_db(file_content, &HeaderDb::standard())
+}
+
+/// Find symbols of the standard library used in a C++ file, but not declared by any header it includes
+pub fn get_missing_headers(file_content: &str) -> Vec<MissingHeader>
+{
+    get_missing_headers_with_db(file_content, &HeaderDb::standard(), Dialect::Cpp, &[])
+}
+
+/// A symbol of the standard library used in a file without including any header declaring it
+#[derive(Debug, PartialEq)]
+pub struct MissingHeader
+{
+    // The symbol as it is called inside `namespace std`, e.g. `chrono::seconds`
+    pub symbol: String,
+    // Headers declaring the symbol, including any of them is enough
+    pub headers: Vec<String>,
+    // The symbol is qualified with `std::` or called, so it is surely not a name of the file itself
+    pub is_reliable: bool,
+}
+
+/// A header or a symbol of the standard library not available in the standard a file is written in
+#[derive(Debug, PartialEq)]
+pub struct VersionIssue
+{
+    // A header in brackets, e.g. `<span>`, or a symbol, e.g. `std::make_unique` or `ATOMIC_VAR_INIT`
+    pub name: String,
+    pub problem: VersionProblem,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VersionProblem
+{
+    // Available since a later standard
+    Introduced(Standard),
+    Removed(Standard),
+    Deprecated(Standard),
+}
+
+impl VersionProblem
+{
+    /// Check what is wrong with using something available as described in `standard`, if anything
+    fn find(availability: Availability, standard: Standard) -> Option<VersionProblem>
+    {
+        match availability
+        {
+            Availability { introduced: Some(introduced), .. } if introduced > standard => Some(VersionProblem::Introduced(introduced)),
+            Availability { removed: Some(removed), .. } if removed <= standard => Some(VersionProblem::Removed(removed)),
+            Availability { deprecated: Some(deprecated), .. } if deprecated <= standard => Some(VersionProblem::Deprecated(deprecated)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for VersionProblem
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            VersionProblem::Introduced(standard) => write!(f, "introduced in {}", standard),
+            VersionProblem::Removed(standard) => write!(f, "removed in {}", standard),
+            VersionProblem::Deprecated(standard) => write!(f, "deprecated in {}", standard),
+        }
+    }
+}
+
+/// A name read from a file, e.g. `std::chrono::seconds`
+struct Name
+{
+    // Identifiers separated by `::`, e.g. `std`, `chrono` and `seconds`
+    components: Vec<String>,
+    // Starts with `::`
+    is_global: bool,
+    // Follows `.` or `->`, or `::` after a template, e.g. `size` in `v.size()`
+    is_member: bool,
+    // Is being declared, e.g. `time` in `int time;` or `exit` in `void exit();`
+    is_declaration: bool,
+    // Token following the name
+    next: Option<Token>,
+}
+
+/// Keywords followed by an expression or a label rather than by a type, e.g. `return`
+const EXPRESSION_KEYWORDS: &[&str] = &[
+    "return", "case", "throw", "else", "do", "new", "delete", "sizeof", "alignof", "co_await", "co_return", "co_yield",
+    "goto", "using", "namespace", "defined", "and", "or", "not", "xor", "bitand", "bitor", "compl", "and_eq", "or_eq",
+    "xor_eq", "not_eq",
+];
+
+/// Keywords followed by a type rather than by a declared name, e.g. `const`
+const TYPE_KEYWORDS: &[&str] = &[
+    "const", "volatile", "static", "extern", "inline", "constexpr", "consteval", "constinit", "typename", "mutable",
+    "thread_local", "register", "friend", "virtual", "explicit", "typedef",
+];
+
+/// Check if a single identifier at `pos`, followed by `next`, is being declared, i.e. follows a type
+///
+/// E.g. `time` in `int time;`, `std::vector<int> time;` or `FILE* open();`, but not in `return time;`
+/// or `a > time(0)`. Names after `struct` and the like are declared only when a definition or
+/// a forward declaration follows, `tm` in `struct tm t;` is used.
+fn is_declaration(tokens: &[Token], pos: usize, next: Option<&Token>) -> bool
+{
+    let is_operator = |token: Option<&Token>, op: &str| matches!(token, Some(Token::Operator(s)) if s == op);
+    let is_type_end = |token: Option<&Token>| match token
+    {
+        Some(Token::Identifier(s)) => !EXPRESSION_KEYWORDS.contains(&s.as_str()),
+        _ => is_operator(token, ">"),
+    };
+    let previous = |i: usize| if i > 0 { tokens.get(i - 1) } else { None };
+
+    match previous(pos)
+    {
+        Some(Token::Identifier(s)) if matches!(s.as_str(), "struct" | "class" | "union" | "enum") => {
+            let is_single_colon = is_operator(next, ":") && !is_operator(tokens.get(pos + 2), ":");
+            is_operator(next, "{") || is_operator(next, ";") || is_single_colon || next == Some(&Token::Identifier("final".into()))
+        },
+        Some(Token::Identifier(s)) => !EXPRESSION_KEYWORDS.contains(&s.as_str()) && !TYPE_KEYWORDS.contains(&s.as_str()),
+        // std::vector<int> time; but not a > time(0)
+        Some(Token::Operator(s)) if s == ">" => [";", "=", "{", "[", ",", ")"].iter().any(|op| is_operator(next, op)),
+        // FILE* fp; but not x * time(0)
+        Some(Token::Operator(s)) if s == "*" || s == "&" => {
+            let mut i = pos - 1;
+            while is_operator(tokens.get(i), "*") || is_operator(tokens.get(i), "&")
+            {
+                if i == 0
+                {
+                    return false;
+                }
+                i -= 1;
+            }
+            is_type_end(tokens.get(i)) && !is_operator(next, "(")
+        },
+        _ => false,
+    }
+}
+
+fn is_scope_operator(tokens: &[Token], pos: usize) -> bool
+{
+    let colon = Token::Operator(":".into());
+    tokens.get(pos) == Some(&colon) && tokens.get(pos + 1) == Some(&colon)
+}
+
+/// Read all names from tokens without whitespace, comments and literals
+fn read_names(tokens: &[Token]) -> Vec<Name>
+{
+    let mut res = Vec::<Name>::new();
+    let mut pos = 0;
+    while pos < tokens.len()
+    {
+        let starts_with_scope = is_scope_operator(tokens, pos);
+        let start = if starts_with_scope { pos + 2 } else { pos };
+        if !matches!(tokens.get(start), Some(Token::Identifier(_)))
+        {
+            pos += 1;
+            continue;
+        }
+
+        let previous = if pos > 0 { tokens.get(pos - 1) } else { None };
+        let is_member = match previous
+        {
+            Some(Token::Operator(s)) if s == "." => true,
+            // p->x
+            Some(Token::Operator(s)) if s == ">" && pos >= 2 && tokens[pos - 2] == Token::Operator("-".into()) => true,
+            // std::vector<int>::iterator
+            Some(Token::Operator(s)) if starts_with_scope && (s == ">" || s == ")") => true,
+            _ => false,
+        };
+
+        let mut components = Vec::<String>::new();
+        let mut end = start;
+        while let Some(Token::Identifier(component)) = tokens.get(end)
+        {
+            components.push(component.clone());
+            end += 1;
+            if is_scope_operator(tokens, end) && matches!(tokens.get(end + 2), Some(Token::Identifier(_)))
+            {
+                end += 2;
+            }
+            else
+            {
+                break;
+            }
+        }
+
+        let is_declaration = !starts_with_scope && components.len() == 1 && is_declaration(tokens, start, tokens.get(end));
+        res.push(Name {
+            components,
+            is_global: starts_with_scope && !is_member,
+            is_member,
+            is_declaration,
+            next: tokens.get(end).cloned(),
+        });
+        pos = end;
+    }
     res
 }
 
-pub fn get_unused_headers(file_content: &str) -> Vec<String>
+/// Names used in a file, as they are called inside `namespace std`
+///
+/// All prefixes are recorded, e.g. `chrono` and `chrono::seconds` for `std::chrono::seconds`.
+/// Names declared in the file, e.g. a variable `time` or a function `exit`, refer to these
+/// declarations when used without a namespace, so they are not recorded then.
+struct UsedNames
+{
+    // Names referring to `std`, e.g. `vector` for `std::vector`, or for `vector` after `using namespace std;`
+    std_names: HashSet<String>,
+    // Names used without a namespace, e.g. `printf`
+    unqualified_names: HashSet<String>,
+    // Names of std_names qualified with `std::` or a namespace alias, or called
+    reliable_std_names: HashSet<String>,
+    // Names of unqualified_names which are called
+    reliable_unqualified_names: HashSet<String>,
+}
+
+impl UsedNames
+{
+    fn new(file_content: &str) -> UsedNames
+    {
+        let significant = |content: &str| -> Vec<Token> {
+            tokenize(content).into_iter().filter(|t| matches!(t, Token::Identifier(_) | Token::Operator(_))).collect()
+        };
+        let mut names = read_names(&significant(&preprocessor::remove_preprocessor_directives(file_content)));
+        // Macros may be used by other macros and in conditions, e.g. `#if INT_MAX > 0xFFFF`
+        for expression in preprocessor::get_directive_expressions(file_content)
+        {
+            names.extend(read_names(&significant(&expression)));
+        }
+
+        // Namespaces of `std` made visible by using-directives, e.g. `` for `using namespace std;`
+        let mut namespaces = Vec::<Vec<String>>::new();
+        // Names of `std` made visible by using-declarations and namespace aliases,
+        // e.g. `fs` for `namespace fs = std::filesystem;`
+        let mut aliases = HashMap::<String, Vec<String>>::new();
+
+        // Directives are applied in order, so a using-directive may rely on a previous one,
+        // e.g. `using namespace std; using namespace chrono;`
+        for (i, name) in names.iter().enumerate()
+        {
+            let keyword = name.components.join("::");
+            let (target, alias) = match (keyword.as_str(), names.get(i + 1), names.get(i + 2))
+            {
+                // using namespace std::chrono;
+                ("using", Some(n), Some(target)) if n.components == ["namespace"] => (target, None),
+                // namespace fs = std::filesystem;
+                ("namespace", Some(alias), Some(target)) if alias.next == Some(Token::Operator("=".into())) => {
+                    (target, Some(alias.components.join("::")))
+                },
+                // using std::swap;
+                ("using", Some(target), _) if target.next == Some(Token::Operator(";".into())) => {
+                    (target, Some(target.components.last().unwrap().clone()))
+                },
+                _ => continue,
+            };
+            for path in resolve(target, &namespaces, &aliases)
+            {
+                match &alias
+                {
+                    Some(alias) => { aliases.insert(alias.clone(), path); },
+                    None if !namespaces.contains(&path) => namespaces.push(path),
+                    None => {},
+                }
+            }
+        }
+
+        let declared: HashSet<&str> = names
+            .iter()
+            .filter(|name| name.is_declaration)
+            .map(|name| name.components[0].as_str())
+            .collect();
+
+        let mut used = UsedNames {
+            std_names: HashSet::new(),
+            unqualified_names: HashSet::new(),
+            reliable_std_names: HashSet::new(),
+            reliable_unqualified_names: HashSet::new(),
+        };
+        for name in names.iter().filter(|name| !name.is_member && !name.is_declaration)
+        {
+            let is_qualified = name.is_global || name.components.len() > 1 || name.components[0] == "std";
+            if !is_qualified && declared.contains(name.components[0].as_str())
+            {
+                continue;
+            }
+            let is_call = name.next == Some(Token::Operator("(".into()));
+            let is_std_qualified = name.components[0] == "std" || (name.components.len() > 1 && aliases.contains_key(&name.components[0]));
+            for path in resolve(name, &namespaces, &aliases)
+            {
+                // std::views is std::ranges::views
+                if path.len() > 1 && path[0] == "ranges" && path[1] == "views"
+                {
+                    insert_prefixes(&mut used.std_names, &path[1..]);
+                }
+                insert_prefixes(&mut used.std_names, &path);
+                if is_call || is_std_qualified
+                {
+                    insert_prefixes(&mut used.reliable_std_names, &path);
+                }
+            }
+            if !name.is_global && name.components[0] != "std"
+            {
+                insert_prefixes(&mut used.unqualified_names, &name.components);
+                if is_call
+                {
+                    insert_prefixes(&mut used.reliable_unqualified_names, &name.components);
+                }
+            }
+        }
+        used
+    }
+
+    /// All used names, sorted, except namespaces known through their members in `db`,
+    /// e.g. `ranges` for `ranges::sort`, as problems are reported for the members
+    fn names_to_report(&self, db: &HeaderDb) -> Vec<&String>
+    {
+        let mut names: Vec<&String> = self.std_names.union(&self.unqualified_names).collect();
+        names.sort();
+        names
+            .iter()
+            .filter(|name| {
+                let prefix = format!("{}::", name);
+                !names.iter().any(|n| n.starts_with(&prefix) && !db.headers_declaring(n).is_empty())
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Check if a symbol of `header` is used, e.g. `chrono::seconds` of `chrono`
+    ///
+    /// Symbols named as in the global namespace, e.g. of the C library, and macros are used without `std::`.
+    fn is_used(&self, symbol: &str, header: &str, db: &HeaderDb) -> bool
+    {
+        self.std_names.contains(symbol)
+            || ((db.has_global_symbols(header) || is_macro_name(symbol)) && self.unqualified_names.contains(symbol))
+    }
+
+    /// Check if a symbol of `header` may be used, see is_used
+    ///
+    /// Unqualified calls count for every header, e.g. `sort(v.begin(), v.end())` may find
+    /// `std::sort` through argument-dependent lookup.
+    fn may_be_used(&self, symbol: &str, header: &str, db: &HeaderDb) -> bool
+    {
+        self.is_used(symbol, header, db) || self.reliable_unqualified_names.contains(symbol)
+    }
+
+    /// Check if a symbol of `header` is used qualified with `std::` or called, see is_used
+    fn is_reliably_used(&self, symbol: &str, header: &str, db: &HeaderDb) -> bool
+    {
+        self.reliable_std_names.contains(symbol)
+            || ((db.has_global_symbols(header) || is_macro_name(symbol)) && self.reliable_unqualified_names.contains(symbol))
+    }
+}
+
+/// Names in `std` a name may refer to, e.g. `chrono::seconds` for `std::chrono::seconds`,
+/// or for `seconds` after `using namespace std::chrono;`
+fn resolve(name: &Name, namespaces: &[Vec<String>], aliases: &HashMap<String, Vec<String>>) -> Vec<Vec<String>>
+{
+    let components = &name.components;
+    if components[0] == "std"
+    {
+        return vec![components[1..].to_vec()];
+    }
+    if name.is_global || name.is_member
+    {
+        return vec![];
+    }
+
+    let mut res = Vec::<Vec<String>>::new();
+    if let Some(alias) = aliases.get(&components[0])
+    {
+        res.push([alias.as_slice(), &components[1..]].concat());
+    }
+    for namespace in namespaces
+    {
+        res.push([namespace.as_slice(), components].concat());
+    }
+    res
+}
+
+fn insert_prefixes(names: &mut HashSet<String>, components: &[String])
+{
+    for len in 1..=components.len()
+    {
+        names.insert(components[..len].join("::"));
+    }
+}
+
+/// Check if a symbol looks like a macro, e.g. `EXIT_SUCCESS` or `ATOMIC_FLAG_INIT`
+fn is_macro_name(symbol: &str) -> bool
+{
+    symbol.chars().any(|ch| ch.is_ascii_uppercase()) && !symbol.chars().any(|ch| ch.is_ascii_lowercase())
+}
+
+/// Find headers from `db` included, but not used in a file, see get_unused_headers
+///
+/// A symbol is used if its name appears as a whole, qualified with `std::` or made visible by
+/// `using namespace std;`, `using std::x;` or a namespace alias, or called without a namespace, as
+/// argument-dependent lookup may find it. Names in the bodies of `#define`
+/// and the conditions of `#if` and the like count as well, other directives like `#include` don't.
+/// Headers included with `// IWYU pragma: keep` or exported with IWYU pragmas are never unused.
+pub fn get_unused_headers_with_db(file_content: &str, db: &HeaderDb) -> Vec<String>
 {
     let mut res = Vec::<String>::new();
 
     let all_headers = preprocessor::get_standard_includes(file_content);
+    let pragmas = iwyu::parse_pragmas(file_content);
+    let used = UsedNames::new(&comment_remover::remove_comments(file_content));
+
+    for h in all_headers
+    {
+        if !db.contains(&h) || res.contains(&h) || pragmas.keeps(&format!("<{}>", h))
+        {
+            continue;
+        }
+
+        if !db.symbols(&h).iter().any(|symbol| used.may_be_used(symbol, &h, db))
+        {
+            res.push(h);
+        }
+    }
+
+    res
+}
+
+/// Find symbols from `db` used in a file, but not declared by any header it includes, sorted by symbol
+///
+/// Symbols are found the same way as in get_unused_headers_with_db. Headers providing a symbol only
+/// through the headers they include are not suggested, e.g. `iostream` for `std::endl`, and
+/// private headers are replaced by the public ones to include instead.
+/// In C, C library headers are suggested by their C names, e.g. `stdio.h` instead of `cstdio`.
+///
+/// Symbols only named without a namespace, e.g. `size_t`, may be the file's own, see MissingHeader::is_reliable.
+///
+/// `exported` are headers exported by the files the file includes, e.g. with `// IWYU pragma: export`,
+/// whose symbols are provided as well. So are the symbols of header units, e.g. `import <vector>;`,
+/// and everything but macros with `import std;`, which leaves out names used without `std::`
+/// unless it is `import std.compat;`.
+pub fn get_missing_headers_with_db(file_content: &str, db: &HeaderDb, dialect: Dialect, exported: &[String]) -> Vec<MissingHeader>
+{
+    let mut res = Vec::<MissingHeader>::new();
 
-    let mut stripped_file_content = comment_remover::remove_comments(file_content);
-    stripped_file_content = string_remover::remove_strings(&stripped_file_content);
-    stripped_file_content = preprocessor::remove_preprocessor_directives(&stripped_file_content);
+    let used = UsedNames::new(&comment_remover::remove_comments(file_content));
+    let provided: HashSet<&str> = preprocessor::get_standard_includes(file_content)
+        .iter()
+        .chain(exported)
+        .flat_map(|h| db.symbols(h))
+        .collect();
+    let modules = preprocessor::get_imported_modules(file_content);
+    let imports_std_compat = modules.iter().any(|m| m == "std.compat");
+    let imports_std = imports_std_compat || modules.iter().any(|m| m == "std");
 
-    for (h, definitions) in make_standard_headers_map()
+    for symbol in used.names_to_report(db)
     {
-        if !all_headers.contains(&h.to_string())
+        let is_imported = imports_std
+            && !is_macro_name(symbol)
+            && (imports_std_compat || used.std_names.contains(symbol.as_str()));
+        if provided.contains(symbol.as_str()) || is_imported
         {
             continue;
         }
 
-        let mut is_used = false;
-        for definition in definitions
+        let mut headers = Vec::<String>::new();
+        let mut is_reliable = false;
+        for declaring in db.headers_declaring(symbol).into_iter().filter(|h| used.is_used(symbol, h, db))
         {
-            if stripped_file_content.contains(&definition.to_string())
+            is_reliable |= used.is_reliably_used(symbol, declaring, db);
+            for h in db.public_headers(declaring)
             {
-                is_used = true;
-                break;
+                let h = if dialect == Dialect::C { db.c_name(h) } else { h };
+                if !headers.iter().any(|header| header == h)
+                {
+                    headers.push(h.into());
+                }
             }
         }
+        if !headers.is_empty()
+        {
+            res.push(MissingHeader { symbol: symbol.to_string(), headers, is_reliable });
+        }
+    }
+
+    res
+}
+
+/// Find headers included and symbols from `db` used in a file which are too new for `standard`,
+/// removed or deprecated in it
+///
+/// Headers come first, in the order they are included, followed by symbols sorted by name.
+/// A symbol declared by several headers is only reported if it has a problem in all of them,
+/// or in all of them provided by the included headers if there are any.
+pub fn get_version_issues_with_db(file_content: &str, db: &HeaderDb, standard: Standard) -> Vec<VersionIssue>
+{
+    let mut res = Vec::<VersionIssue>::new();
+
+    let includes = preprocessor::get_standard_includes(file_content);
+    for header in &includes
+    {
+        let name = format!("<{}>", header);
+        if let Some(problem) = VersionProblem::find(db.header_availability(header), standard)
+            && !res.iter().any(|issue| issue.name == name)
+        {
+            res.push(VersionIssue { name, problem });
+        }
+    }
+    let provided: Vec<&str> = includes
+        .iter()
+        .flat_map(|header| std::iter::once(header.as_str()).chain(db.included_headers(header)))
+        .collect();
 
-        if !is_used
+    let used = UsedNames::new(&comment_remover::remove_comments(file_content));
+    for symbol in used.names_to_report(db)
+    {
+        let mut headers: Vec<&str> = db.headers_declaring(symbol).into_iter().filter(|h| used.is_used(symbol, h, db)).collect();
+        if headers.iter().any(|h| provided.contains(h))
+        {
+            headers.retain(|h| provided.contains(h));
+        }
+        // None if the symbol is fine in any of the headers
+        let problems: Option<Vec<VersionProblem>> = headers
+            .into_iter()
+            .map(|h| VersionProblem::find(db.symbol_availability(symbol, h), standard))
+            .collect();
+        if let Some(problem) = problems.and_then(|problems| problems.into_iter().next())
         {
-            res.push(h.to_string());
+            let name = if is_macro_name(symbol) { symbol.to_string() } else { format!("std::{}", symbol) };
+            res.push(VersionIssue { name, problem });
         }
     }
 
@@ -221,7 +545,7 @@ pub fn get_unused_headers(file_content: &str) -> Vec<String>
 
 #[cfg(test)]
 mod test {
-    use crate::standard_headers::get_unused_headers;
+    use super::*;
 
     #[test]
     fn simple_unused_include()
@@ -240,4 +564,314 @@ mod test {
         ";
         assert_eq!(get_unused_headers(input), Vec::<String>::new());
     }
+
+    #[test]
+    fn whole_identifiers()
+    {
+        let input = "
+            #include <list>
+            #include <map>
+            #include <string>
+
+            std::forward_list<int> l;
+            std::unordered_map<int, int> m;
+            int stringLength; // std::string
+            const char* s = \"std::string\";
+        ";
+        assert_eq!(get_unused_headers(input), vec!["list", "map", "string"]);
+    }
+
+    #[test]
+    fn using_namespace_and_declarations()
+    {
+        let input = "
+            #include <vector>
+            #include <map>
+            #include <chrono>
+            #include <filesystem>
+            #include <utility>
+            #include <thread>
+
+            using namespace std;
+            using namespace chrono;
+            namespace fs = std::filesystem;
+            using std::swap;
+
+            vector<int> v;
+            seconds timeout;
+            fs::path p;
+            void f(int& a, int& b) { swap(a, b); }
+            void g() { this_thread::yield(); }
+            void h(const Config& c) { c.map(); }
+        ";
+        assert_eq!(get_unused_headers(input), vec!["map"]);
+    }
+
+    #[test]
+    fn ranges_and_views()
+    {
+        let input = "
+            #include <algorithm>
+            #include <ranges>
+
+            void f(std::vector<int>& v) { std::ranges::sort(v); }
+            auto even = v | std::ranges::views::filter(is_even);
+        ";
+        assert_eq!(get_unused_headers(input), Vec::<String>::new());
+
+        let input = "
+            #include <ranges>
+            namespace views = std::views;
+            auto even = v | views::filter(is_even);
+        ";
+        assert_eq!(get_unused_headers(input), Vec::<String>::new());
+    }
+
+    #[test]
+    fn c_headers_and_macros()
+    {
+        let input = "
+            #include <cstdio>
+            #include <stdlib.h>
+            #include <atomic>
+
+            int main() { printf(\"%d\", EXIT_SUCCESS); }
+            std::atomic_flag flag = ATOMIC_FLAG_INIT;
+        ";
+        assert_eq!(get_unused_headers(input), Vec::<String>::new());
+    }
+
+    #[test]
+    fn unqualified_calls()
+    {
+        // Argument-dependent lookup finds std::sort and std::getline
+        let input = "
+            #include <algorithm>
+            #include <string>
+            #include <vector>
+            #include <iostream>
+
+            void f(std::vector<int>& v) { sort(v.begin(), v.end()); }
+            void g(std::string& s) { getline(std::cin, s); }
+        ";
+        assert_eq!(get_unused_headers(input), Vec::<String>::new());
+        assert_eq!(get_missing_headers(input), vec![]);
+
+        let input = "
+            #include <algorithm>
+            #include <string>
+
+            struct S { int sort; };
+            int f(S s) { return s.sort; }
+            int getline;
+        ";
+        assert_eq!(get_unused_headers(input), vec!["algorithm", "string"]);
+    }
+
+    #[test]
+    fn declared_names()
+    {
+        let input = "
+            #include <ctime>
+            #include <cmath>
+            #include <cstdlib>
+            #include <csignal>
+            #include <cstdio>
+
+            struct Clock { int time; double log; };
+            void exit();
+            int signal = 0;
+            void f(Clock* c) { exit(); c->time = signal; }
+        ";
+        assert_eq!(get_unused_headers(input), vec!["ctime", "cmath", "cstdlib", "csignal", "cstdio"]);
+        assert_eq!(get_missing_headers(input), vec![]);
+
+        let used = "
+            #include <ctime>
+            #include <cmath>
+            #include <cstdio>
+
+            double f(double x) { return log(x) * time(nullptr); }
+            const FILE* file;
+        ";
+        assert_eq!(get_unused_headers(used), Vec::<String>::new());
+
+        let mut db = HeaderDb::standard();
+        db.merge(HeaderDb::builtin("posix").unwrap());
+        let posix = "
+            #include <unistd.h>
+            #include <fcntl.h>
+
+            struct File { int open; int close(); };
+            ssize_t read(int fd);
+        ";
+        assert_eq!(get_unused_headers_with_db(posix, &db), vec!["unistd.h", "fcntl.h"]);
+    }
+
+    #[test]
+    fn iwyu_pragmas_and_mappings()
+    {
+        let input = "
+            #include <vector> // IWYU pragma: keep
+            // IWYU pragma: begin_exports
+            #include <map>
+            // IWYU pragma: end_exports
+            #include <set>
+
+            std::string name;
+            auto x = std::frobnicate();
+        ";
+        assert_eq!(get_unused_headers(input), vec!["set"]);
+
+        let mut db = HeaderDb::standard();
+        db.merge(HeaderDb::parse("[headers]\n\"bits/frobnicate.h\" = \"frobnicate\"").unwrap());
+        db.add_mapping(&iwyu::Mapping::Include { header: "<bits/frobnicate.h>".into(), private: true, public: "<utility>".into() });
+        assert_eq!(get_missing_headers_with_db(input, &db, Dialect::Cpp, &["string".into()]), vec![
+            MissingHeader { symbol: "frobnicate".into(), headers: vec!["utility".into()], is_reliable: true },
+        ]);
+    }
+
+    #[test]
+    fn other_header_dbs()
+    {
+        let mut db = HeaderDb::standard();
+        db.merge(HeaderDb::builtin("posix").unwrap());
+        db.merge(HeaderDb::builtin("boost").unwrap());
+        let input = "
+            #include <unistd.h>
+            #include <pthread.h>
+            #include <boost/optional.hpp>
+
+            void f(int fd) { close(fd); }
+            boost::optional<int> value;
+        ";
+        assert_eq!(get_unused_headers_with_db(input, &db), vec!["pthread.h"]);
+    }
+
+    #[test]
+    fn macros_in_directives()
+    {
+        let input = "
+            #include <cassert>
+            #include <climits>
+            #include <cerrno>
+            #include <cstdio>
+
+            #define CHECK(x) assert(x)
+            #if INT_MAX > 0xFFFF
+            #endif
+            #undef EOF
+            #define errno my_errno
+        ";
+        assert_eq!(get_unused_headers(input), vec!["cerrno", "cstdio"]);
+    }
+
+    #[test]
+    fn missing_headers()
+    {
+        let input = "
+            #include <iostream>
+            #include <vector>
+
+            std::optional<int> find(const std::vector<int>& v);
+            void print() { std::cout << std::endl; }
+            std::chrono::seconds timeout;
+            std::size_t count;
+            int x = v.size();
+            auto m = std::ranges::max(v);
+        ";
+        assert_eq!(get_missing_headers(input), vec![
+            MissingHeader { symbol: "chrono".into(), headers: vec!["chrono".into()], is_reliable: true },
+            MissingHeader { symbol: "optional".into(), headers: vec!["optional".into()], is_reliable: true },
+            MissingHeader { symbol: "ranges::max".into(), headers: vec!["algorithm".into()], is_reliable: true },
+            MissingHeader {
+                symbol: "size_t".into(),
+                headers: vec!["cstddef", "cstdio", "cstdlib", "cstring", "ctime", "cuchar", "cwchar"].into_iter().map(String::from).collect(),
+                is_reliable: true,
+            },
+        ]);
+    }
+
+    #[test]
+    fn missing_headers_with_imports()
+    {
+        let input = "
+            import std;
+            import <cassert>;
+
+            std::vector<int> v;
+            void f() { assert(v.empty()); printf(\"%d\", INT_MAX); }
+        ";
+        assert_eq!(get_missing_headers(input), vec![
+            MissingHeader { symbol: "INT_MAX".into(), headers: vec!["climits".into()], is_reliable: false },
+            MissingHeader { symbol: "printf".into(), headers: vec!["cstdio".into()], is_reliable: true },
+        ]);
+        let input = "
+            import std.compat;
+
+            std::vector<int> v;
+            void f() { printf(\"%zu\", v.size()); }
+        ";
+        assert_eq!(get_missing_headers(input), vec![]);
+    }
+
+    #[test]
+    fn missing_headers_in_c()
+    {
+        let input = "
+            #include <stdio.h>
+
+            int main(void) { printf(\"%d\", EXIT_SUCCESS); return strlen(\"\"); }
+        ";
+        let missing = get_missing_headers_with_db(input, &HeaderDb::standard(), Dialect::C, &[]);
+        assert_eq!(missing, vec![
+            MissingHeader { symbol: "EXIT_SUCCESS".into(), headers: vec!["stdlib.h".into()], is_reliable: false },
+            MissingHeader { symbol: "strlen".into(), headers: vec!["string.h".into()], is_reliable: true },
+        ]);
+    }
+
+    #[test]
+    fn version_issues()
+    {
+        let input = "
+            #include <span>
+            #include <memory>
+            #include <ciso646>
+            #include <algorithm>
+
+            std::span<int> s;
+            auto p = std::make_unique<int>(1);
+            std::auto_ptr<int> a;
+            std::shared_ptr<int> sp;
+            void f(std::vector<int>& v) { std::ranges::sort(v); std::random_shuffle(v.begin(), v.end()); }
+        ";
+        let issue = |name: &str, problem| VersionIssue { name: name.into(), problem };
+        assert_eq!(get_version_issues_with_db(input, &HeaderDb::standard(), Standard::Cpp11), vec![
+            issue("<span>", VersionProblem::Introduced(Standard::Cpp20)),
+            issue("std::auto_ptr", VersionProblem::Deprecated(Standard::Cpp11)),
+            issue("std::make_unique", VersionProblem::Introduced(Standard::Cpp14)),
+            issue("std::ranges::sort", VersionProblem::Introduced(Standard::Cpp20)),
+            issue("std::span", VersionProblem::Introduced(Standard::Cpp20)),
+        ]);
+        assert_eq!(get_version_issues_with_db(input, &HeaderDb::standard(), Standard::Cpp20), vec![
+            issue("<ciso646>", VersionProblem::Removed(Standard::Cpp20)),
+            issue("std::auto_ptr", VersionProblem::Removed(Standard::Cpp17)),
+            issue("std::random_shuffle", VersionProblem::Removed(Standard::Cpp17)),
+        ]);
+
+        // The std::move of <algorithm> is in C++98, unlike the one of <utility>
+        let input = "
+            #include <algorithm>
+            #include <vector>
+            void f(std::vector<int>& a, std::vector<int>& b) { std::move(a.begin(), a.end(), b.begin()); }
+        ";
+        assert_eq!(get_version_issues_with_db(input, &HeaderDb::standard(), Standard::Cpp98), vec![]);
+        let input = "
+            #include <utility>
+            int f(int a) { return std::move(a); }
+        ";
+        assert_eq!(get_version_issues_with_db(input, &HeaderDb::standard(), Standard::Cpp98), vec![
+            issue("std::move", VersionProblem::Introduced(Standard::Cpp11)),
+        ]);
+    }
 }
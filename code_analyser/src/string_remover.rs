@@ -11,7 +11,6 @@ enum ParserState {
 /// It greatly simplifies the future parsing since strings can contain special characters.
 ///
 /// It is assumed that the user already called `remove_comments` function.
-
 pub fn remove_strings(file_content: &str) -> String {
     let mut result: String = Default::default();
     let mut state: ParserState = ParserState::Normal;
@@ -53,7 +52,7 @@ pub fn remove_strings(file_content: &str) -> String {
             },
         }
     }
-    return result;
+    result
 }
 
 #[cfg(test)]
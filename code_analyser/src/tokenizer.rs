@@ -1,7 +1,9 @@
+use std::fmt;
 use std::string::String;
 
 
 #[derive(Debug)]
+#[derive(Clone)]
 #[derive(PartialEq)]
 pub enum Token
 {
@@ -36,14 +38,35 @@ pub enum Token
     Identifier(String),
 }
 
+impl Token
+{
+    /// Text of the token as written in the file
+    pub fn text(&self) -> &str
+    {
+        match self
+        {
+            Token::Unknown(s) | Token::LineContinuation(s) | Token::NewLine(s) | Token::WhiteSpace(s) | Token::Comment(s)
+            | Token::String(s) | Token::Char(s) | Token::Number(s) | Token::Operator(s) | Token::Identifier(s) => s,
+        }
+    }
+}
+
+impl fmt::Display for Token
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.text())
+    }
+}
+
 
 fn read_whitespace(mut s: &str) -> String
 {
     let mut res = String::new();
 
-    while s.len() > 0
+    while !s.is_empty()
     {
-        let ch = s.bytes().nth(0).unwrap();
+        let ch = s.as_bytes()[0];
 
         if ch != b' ' && ch != b'\t'
         {
@@ -62,7 +85,7 @@ fn read_single_line_comment(mut s: &str) -> String
 {
     let mut res = String::new();
 
-    while s.len() > 0
+    while !s.is_empty()
     {
         if s.starts_with("\\\n") || s.starts_with("\\\r")
         {
@@ -94,7 +117,7 @@ fn read_multi_line_comment(mut s: &str) -> String
     res.push_str(&s[..2]);
     s = &s[2..];
 
-    while s.len() > 0
+    while !s.is_empty()
     {
         if s.starts_with("*/")
         {
@@ -119,7 +142,7 @@ fn read_string(mut s: &str) -> String
     res.push_str(&s[..1]);
     s = &s[1..];
 
-    while s.len() > 0
+    while !s.is_empty()
     {
         if s.starts_with("\\\"")
         {
@@ -151,7 +174,7 @@ fn read_char(mut s: &str) -> String
     res.push_str(&s[..1]);
     s = &s[1..];
 
-    while s.len() > 0
+    while !s.is_empty()
     {
         if s.starts_with("\\'")
         {
@@ -180,10 +203,10 @@ fn read_unsigned_int(mut s: &str) -> String
 {
     let mut res = String::new();
 
-    while s.len() > 0
+    while !s.is_empty()
     {
-        let ch = s.bytes().nth(0).unwrap();
-        if b'0' <= ch && ch <= b'9'
+        let ch = s.as_bytes()[0];
+        if ch.is_ascii_digit()
         {
             res.push(ch as char);
             s = &s[1..];
@@ -250,10 +273,10 @@ fn read_identifier(mut s: &str) -> String
 {
     let mut res = String::new();
 
-    while s.len() > 0
+    while !s.is_empty()
     {
-        let ch = s.bytes().nth(0).unwrap();
-        if (b'0' <= ch && ch <= b'9') || (b'a' <= ch && ch <= b'z') || (b'A' <= ch && ch <= b'Z') || (ch == b'_')
+        let ch = s.as_bytes()[0];
+        if ch.is_ascii_digit() || ch.is_ascii_lowercase() || ch.is_ascii_uppercase() || (ch == b'_')
         {
             res.push(ch as char);
             s = &s[1..];
@@ -273,9 +296,9 @@ pub fn tokenize(file_content: &str) -> Vec<Token>
     let mut s = file_content;
     let mut res = Vec::<Token>::new();
 
-    'outer: while s.len() > 0
+    'outer: while !s.is_empty()
     {
-        let ch = s.bytes().nth(0).unwrap();
+        let ch = s.as_bytes()[0];
 
         if ch == b' ' || ch == b'\t'
         {
@@ -337,7 +360,7 @@ pub fn tokenize(file_content: &str) -> Vec<Token>
             continue 'outer;
         }
 
-        if (b'0' <= ch && ch <= b'9') ||
+        if ch.is_ascii_digit() ||
            s.starts_with(".0") || s.starts_with(".1") || s.starts_with(".2") || s.starts_with(".3") || s.starts_with(".4") ||
            s.starts_with(".5") || s.starts_with(".6") || s.starts_with(".7") || s.starts_with(".8") || s.starts_with(".9")
         {
@@ -358,7 +381,7 @@ pub fn tokenize(file_content: &str) -> Vec<Token>
             }
         }
 
-        if (b'a' <= ch && ch <= b'z') || (b'A' <= ch && ch <= b'Z') || (ch == b'_')
+        if ch.is_ascii_lowercase() || ch.is_ascii_uppercase() || (ch == b'_')
         {
             let val = read_identifier(s);
             s = &s[val.len()..];
@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+
+/// A value of a TOML key
+///
+/// Only the subset of TOML used by configuration files is supported:
+/// strings, integers, booleans, arrays, tables and inline tables. Dates and floats are not.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value
+{
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+pub type Table = BTreeMap<String, Value>;
+
+impl Value
+{
+    pub fn as_str(&self) -> Option<&str>
+    {
+        match self
+        {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool>
+    {
+        match self
+        {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&Table>
+    {
+        match self
+        {
+            Value::Table(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// Strings of an array, or None if the value is not an array of strings
+    pub fn as_string_array(&self) -> Option<Vec<String>>
+    {
+        match self
+        {
+            Value::Array(values) => values.iter().map(|v| v.as_str().map(String::from)).collect(),
+            _ => None,
+        }
+    }
+}
+
+struct Parser
+{
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser
+{
+    fn peek(&self) -> Option<char>
+    {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char>
+    {
+        let ch = self.peek();
+        if ch == Some('\n')
+        {
+            self.line += 1;
+        }
+        self.pos += 1;
+        ch
+    }
+
+    fn error(&self, message: &str) -> String
+    {
+        format!("line {}: {}", self.line, message)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String>
+    {
+        match self.next()
+        {
+            Some(ch) if ch == expected => Ok(()),
+            Some('\n') => Err(format!("line {}: expected `{}`, found end of line", self.line - 1, expected)),
+            Some(ch) => Err(self.error(&format!("expected `{}`, found `{}`", expected, ch))),
+            None => Err(self.error(&format!("expected `{}`, found end of file", expected))),
+        }
+    }
+
+    /// Skip spaces and tabs, but not new lines
+    fn skip_spaces(&mut self)
+    {
+        while matches!(self.peek(), Some(' ' | '\t'))
+        {
+            self.next();
+        }
+    }
+
+    fn skip_comment(&mut self)
+    {
+        if self.peek() == Some('#')
+        {
+            while !matches!(self.peek(), Some('\n') | None)
+            {
+                self.next();
+            }
+        }
+    }
+
+    /// Skip whitespace, new lines and comments, e.g. between array elements
+    fn skip_whitespace_and_comments(&mut self)
+    {
+        loop
+        {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek()
+            {
+                Some('\n' | '\r') => { self.next(); },
+                _ => return,
+            }
+        }
+    }
+
+    /// Expect the end of a line, optionally with a comment
+    fn expect_end_of_line(&mut self) -> Result<(), String>
+    {
+        self.skip_spaces();
+        self.skip_comment();
+        match self.next()
+        {
+            None | Some('\n') => Ok(()),
+            Some('\r') if self.peek() == Some('\n') => { self.next(); Ok(()) },
+            Some(ch) => Err(self.error(&format!("unexpected `{}` at the end of a line", ch))),
+        }
+    }
+
+    /// Read a key, e.g. `name`, `"quoted name"` or `checks.unused-headers`
+    fn read_key(&mut self) -> Result<Vec<String>, String>
+    {
+        let mut key = Vec::new();
+        loop
+        {
+            self.skip_spaces();
+            match self.peek()
+            {
+                Some('"') => key.push(self.read_basic_string()?),
+                Some('\'') => key.push(self.read_literal_string()?),
+                _ => {
+                    let mut part = String::new();
+                    while let Some(ch) = self.peek()
+                    {
+                        if !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+                        {
+                            break;
+                        }
+                        part.push(ch);
+                        self.next();
+                    }
+                    if part.is_empty()
+                    {
+                        return Err(self.error("expected a key"));
+                    }
+                    key.push(part);
+                },
+            }
+            self.skip_spaces();
+            if self.peek() != Some('.')
+            {
+                return Ok(key);
+            }
+            self.next();
+        }
+    }
+
+    fn read_basic_string(&mut self) -> Result<String, String>
+    {
+        self.expect('"')?;
+        let mut res = String::new();
+        loop
+        {
+            match self.next()
+            {
+                Some('"') => return Ok(res),
+                Some('\\') => res.push(match self.next()
+                {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('\\') => '\\',
+                    Some('"') => '"',
+                    Some(ch) => return Err(self.error(&format!("unsupported escape sequence `\\{}`", ch))),
+                    None => return Err(self.error("unterminated string")),
+                }),
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(ch) => res.push(ch),
+            }
+        }
+    }
+
+    fn read_literal_string(&mut self) -> Result<String, String>
+    {
+        self.expect('\'')?;
+        let mut res = String::new();
+        loop
+        {
+            match self.next()
+            {
+                Some('\'') => return Ok(res),
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(ch) => res.push(ch),
+            }
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Value, String>
+    {
+        self.skip_spaces();
+        match self.peek()
+        {
+            Some('"') => Ok(Value::String(self.read_basic_string()?)),
+            Some('\'') => Ok(Value::String(self.read_literal_string()?)),
+            Some('[') => {
+                self.next();
+                let mut values = Vec::new();
+                loop
+                {
+                    self.skip_whitespace_and_comments();
+                    if self.peek() == Some(']')
+                    {
+                        self.next();
+                        return Ok(Value::Array(values));
+                    }
+                    values.push(self.read_value()?);
+                    self.skip_whitespace_and_comments();
+                    match self.peek()
+                    {
+                        Some(',') => { self.next(); },
+                        Some(']') => {},
+                        _ => return Err(self.error("expected `,` or `]` in an array")),
+                    }
+                }
+            },
+            Some('{') => {
+                self.next();
+                let mut table = Table::new();
+                self.skip_spaces();
+                if self.peek() == Some('}')
+                {
+                    self.next();
+                    return Ok(Value::Table(table));
+                }
+                loop
+                {
+                    let key = self.read_key()?;
+                    self.expect('=')?;
+                    let value = self.read_value()?;
+                    self.insert(&mut table, &key, value)?;
+                    self.skip_spaces();
+                    match self.next()
+                    {
+                        Some(',') => {},
+                        Some('}') => return Ok(Value::Table(table)),
+                        _ => return Err(self.error("expected `,` or `}` in an inline table")),
+                    }
+                }
+            },
+            _ => {
+                let mut word = String::new();
+                while let Some(ch) = self.peek()
+                {
+                    if !(ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '+'))
+                    {
+                        break;
+                    }
+                    word.push(ch);
+                    self.next();
+                }
+                match word.as_str()
+                {
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    _ => word.replace('_', "").parse::<i64>()
+                        .map(Value::Integer)
+                        .map_err(|_| self.error(&format!("unsupported value `{}`", word))),
+                }
+            },
+        }
+    }
+
+    /// Insert a value by a dotted key, creating intermediate tables
+    fn insert(&self, table: &mut Table, key: &[String], value: Value) -> Result<(), String>
+    {
+        let table = self.get_table(table, &key[..key.len() - 1])?;
+        let name = &key[key.len() - 1];
+        if table.contains_key(name)
+        {
+            return Err(self.error(&format!("duplicate key `{}`", name)));
+        }
+        table.insert(name.clone(), value);
+        Ok(())
+    }
+
+    /// Get a table by a dotted key, creating it if needed
+    fn get_table<'t>(&self, mut table: &'t mut Table, key: &[String]) -> Result<&'t mut Table, String>
+    {
+        for name in key
+        {
+            let entry = table.entry(name.clone()).or_insert_with(|| Value::Table(Table::new()));
+            table = match entry
+            {
+                Value::Table(t) => t,
+                _ => return Err(self.error(&format!("`{}` is not a table", name))),
+            };
+        }
+        Ok(table)
+    }
+
+    fn parse(&mut self) -> Result<Table, String>
+    {
+        let mut root = Table::new();
+        let mut current_table = Vec::<String>::new();
+        loop
+        {
+            self.skip_whitespace_and_comments();
+            match self.peek()
+            {
+                None => return Ok(root),
+                Some('[') => {
+                    self.next();
+                    if self.peek() == Some('[')
+                    {
+                        return Err(self.error("arrays of tables are not supported"));
+                    }
+                    current_table = self.read_key()?;
+                    self.expect(']')?;
+                    self.get_table(&mut root, &current_table)?;
+                },
+                Some(_) => {
+                    let key = self.read_key()?;
+                    self.expect('=')?;
+                    let value = self.read_value()?;
+                    let table = self.get_table(&mut root, &current_table)?;
+                    self.insert(table, &key, value)?;
+                },
+            }
+            self.expect_end_of_line()?;
+        }
+    }
+}
+
+/// Parse a TOML document into a table
+///
+/// Errors contain the line number where parsing has failed.
+pub fn parse(content: &str) -> Result<Table, String>
+{
+    Parser { chars: content.chars().collect(), pos: 0, line: 1 }.parse()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"
+            # A comment
+            name = "value" # trailing comment
+            literal = 'C:\path'
+            count = 1_000
+            enabled = true
+            list = [
+                "a",  # comment inside an array
+                "b",
+            ]
+            point = { x = 1, y = -2 }
+
+            [section]
+            "quoted key" = "\"escaped\""
+
+            [section.nested]
+            a.b = false
+        "#;
+        let table = parse(input).unwrap();
+        assert_eq!(table["name"], Value::String("value".into()));
+        assert_eq!(table["literal"], Value::String("C:\\path".into()));
+        assert_eq!(table["count"], Value::Integer(1000));
+        assert_eq!(table["enabled"], Value::Boolean(true));
+        assert_eq!(table["list"].as_string_array(), Some(vec!["a".into(), "b".into()]));
+        assert_eq!(table["point"].as_table().unwrap()["y"], Value::Integer(-2));
+        let section = table["section"].as_table().unwrap();
+        assert_eq!(section["quoted key"], Value::String("\"escaped\"".into()));
+        let nested = section["nested"].as_table().unwrap();
+        assert_eq!(nested["a"].as_table().unwrap()["b"], Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("a = 1\na = 2"), Err("line 2: duplicate key `a`".into()));
+        assert_eq!(parse("a = \"unterminated"), Err("line 1: unterminated string".into()));
+        assert_eq!(parse("a = 1 2"), Err("line 1: unexpected `2` at the end of a line".into()));
+        assert_eq!(parse("a = 1\n[a]"), Err("line 2: `a` is not a table".into()));
+        assert_eq!(parse("a\n"), Err("line 1: expected `=`, found end of line".into()));
+    }
+}
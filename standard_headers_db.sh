@@ -0,0 +1,58 @@
+#!/bin/bash
+
+# Load the database of symbols per standard header into the symbolsPerHeader associative array.
+# The database is code_analyser/data/standard_headers.toml, set STANDARD_HEADERS_DB to use another one.
+
+# This file is meant to be sourced.
+
+declare -A symbolsPerHeader=()
+declare -A includesPerHeader=()
+
+standardHeadersDb="${STANDARD_HEADERS_DB:-$(dirname "${BASH_SOURCE[0]}")/code_analyser/data/standard_headers.toml}"
+
+function read_standard_headers_db() {
+    local db="$1"
+    local section=""
+    local entry_pattern='^"?([^"= ]+)"? = (.*)$'
+    local line
+
+    while IFS= read -r line; do
+        if [[ "$line" =~ ^\[(.*)\]$ ]]; then
+            section="${BASH_REMATCH[1]}"
+        elif [[ "$line" =~ $entry_pattern ]]; then
+            local header="${BASH_REMATCH[1]}"
+            local value="${BASH_REMATCH[2]}"
+            if [[ "$section" == headers && "$value" =~ ^\"([^\"]*)\" ]]; then
+                symbolsPerHeader[$header]="${BASH_REMATCH[1]}"
+            elif [[ "$section" == includes && "$value" =~ ^\[([^]]*)\] ]]; then
+                includesPerHeader[$header]="$(echo "${BASH_REMATCH[1]}" | tr -d '",')"
+            fi
+        fi
+    done < "$db"
+}
+
+# Print symbols of a header, and of the headers it includes (recursively)
+function get_symbols_of_header() {
+    local header="$1"
+    local included
+
+    echo "${symbolsPerHeader[$header]}"
+    for included in ${includesPerHeader[$header]}; do
+        get_symbols_of_header "$included"
+    done
+}
+
+function load_standard_headers_db() {
+    read_standard_headers_db "$standardHeadersDb"
+
+    local -A resolved=()
+    local header
+    for header in "${!symbolsPerHeader[@]}" "${!includesPerHeader[@]}"; do
+        resolved[$header]="$(get_symbols_of_header "$header" | xargs)"
+    done
+    for header in "${!resolved[@]}"; do
+        symbolsPerHeader[$header]="${resolved[$header]}"
+    done
+}
+
+load_standard_headers_db